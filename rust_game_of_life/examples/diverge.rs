@@ -0,0 +1,42 @@
+//! Forks a seeded soup and prints how similar it stays under Conway's rule and HighLife.
+//!
+//! `cargo run --example diverge`
+
+use rust_game_of_life::{rule::Rule, universe::Universe, GenerationConfig, SimulationConfig};
+
+fn main() {
+    let conway = SimulationConfig {
+        generation: GenerationConfig {
+            seed: Some(2021),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut high_life = SimulationConfig::default();
+    high_life.set_rule(Rule::parse("B36/S23").unwrap());
+
+    let generation = &conway.generation;
+    let soup = Universe::random(
+        generation.initial_size,
+        generation.life_chance,
+        &mut generation.rng(),
+    );
+    let mut a = soup.fork();
+    let mut b = soup.fork();
+    println!(
+        "generation,similarity,{},{}",
+        conway.rule(),
+        high_life.rule()
+    );
+    for generation in 1..=100 {
+        a.step(&conway);
+        b.step(&high_life);
+        println!(
+            "{},{:.3},{},{}",
+            generation,
+            a.similarity(&b),
+            a.population(),
+            b.population()
+        );
+    }
+}
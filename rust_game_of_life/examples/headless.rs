@@ -0,0 +1,33 @@
+//! Uses the core API without rendering: steps a glider and a seeded soup and prints what happens.
+//!
+//! `cargo run --example headless`
+
+use rand::{rngs::StdRng, SeedableRng};
+use rust_game_of_life::{
+    cell_patterns::CellPattern, universe::Universe, utils::SizeInt, SimulationConfig,
+};
+
+fn main() {
+    let config = SimulationConfig::default();
+
+    let mut glider = Universe::from(&CellPattern::glider());
+    for _ in 0..10 {
+        println!("Generation {}:{}", glider.generation(), glider);
+        glider.step(&config);
+    }
+
+    // The same seed always gives the same soup, see `Universe::generate_seeded` for the rendered version
+    let mut soup = Universe::random(SizeInt::new(32, 32), 0.4, &mut StdRng::seed_from_u64(7));
+    println!("generation,population,bounds");
+    for _ in 0..=50 {
+        let bounds = soup.bounds();
+        println!(
+            "{},{},{}x{}",
+            soup.generation(),
+            soup.population(),
+            bounds.right - bounds.left + 1,
+            bounds.top - bounds.bottom + 1
+        );
+        soup.step(&config);
+    }
+}
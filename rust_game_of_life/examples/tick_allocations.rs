@@ -0,0 +1,50 @@
+//! Counts the heap allocations and time per tick of a seeded soup, to check that ticking stays cheap.
+//!
+//! `cargo run --release --example tick_allocations`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+use rust_game_of_life::{universe::Universe, utils::SizeInt, SimulationConfig};
+
+/// How many ticks are measured after the warmup
+const TICKS: u32 = 100;
+
+/// Forwards to the system allocator, counting every allocation
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() {
+    let config = SimulationConfig::default();
+    let mut soup = Universe::random(SizeInt::new(64, 64), 0.4, &mut StdRng::seed_from_u64(1));
+    // The first ticks of a soup are much busier than the rest
+    soup.advance(10, &config);
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    soup.advance(TICKS, &config);
+    let elapsed = start.elapsed();
+    println!(
+        "allocations per tick: {}",
+        (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / TICKS as usize
+    );
+    println!("time per tick: {:?}", elapsed / TICKS);
+}
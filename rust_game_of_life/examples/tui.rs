@@ -0,0 +1,15 @@
+//! Runs a randomly generated universe in the terminal.
+//!
+//! `cargo run --example tui --features tui`
+
+use rust_game_of_life::{tui, universe::Universe, SimulationConfig};
+
+fn main() -> std::io::Result<()> {
+    let config = SimulationConfig::default();
+    let universe = Universe::random(
+        config.generation.initial_size,
+        config.generation.life_chance,
+        &mut config.generation.rng(),
+    );
+    tui::run(universe, &config)
+}
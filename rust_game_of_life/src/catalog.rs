@@ -0,0 +1,215 @@
+//! Well-known patterns with descriptive metadata, for pattern pickers and the like.
+
+use crate::cell_patterns::CellPattern;
+
+/// What kind of pattern a catalog entry is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternCategory {
+    /// Never changes
+    StillLife,
+    /// Returns to its starting state after a number of generations
+    Oscillator,
+    /// Returns to its starting shape moved somewhere else
+    Spaceship,
+    /// Keeps emitting spaceships
+    Gun,
+    /// Evolves for a long time before stabilizing
+    Methuselah,
+}
+
+/// Descriptive metadata of a catalog pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatternInfo {
+    pub name: &'static str,
+    /// Who found the pattern, `None` for patterns that were never credited to anyone
+    pub discoverer: Option<&'static str>,
+    pub category: PatternCategory,
+    /// How many generations it takes to return to the starting shape, `None` for patterns that don't
+    pub period: Option<u32>,
+    /// How fast a spaceship travels in terms of the speed of light `c`, such as `c/4 diagonal`
+    pub velocity: Option<&'static str>,
+}
+
+/// The catalog entries with their cells as RLE
+const CATALOG: [(PatternInfo, &str); 10] = [
+    (
+        PatternInfo {
+            name: "Block",
+            discoverer: None,
+            category: PatternCategory::StillLife,
+            period: Some(1),
+            velocity: None,
+        },
+        "x = 2, y = 2\n2o$2o!",
+    ),
+    (
+        PatternInfo {
+            name: "Beehive",
+            discoverer: None,
+            category: PatternCategory::StillLife,
+            period: Some(1),
+            velocity: None,
+        },
+        "x = 4, y = 3\nb2o$o2bo$b2o!",
+    ),
+    (
+        PatternInfo {
+            name: "Loaf",
+            discoverer: None,
+            category: PatternCategory::StillLife,
+            period: Some(1),
+            velocity: None,
+        },
+        "x = 4, y = 4\nb2o$o2bo$bobo$2bo!",
+    ),
+    (
+        PatternInfo {
+            name: "Blinker",
+            discoverer: None,
+            category: PatternCategory::Oscillator,
+            period: Some(2),
+            velocity: None,
+        },
+        "x = 3, y = 1\n3o!",
+    ),
+    (
+        PatternInfo {
+            name: "Toad",
+            discoverer: Some("Simon Norton"),
+            category: PatternCategory::Oscillator,
+            period: Some(2),
+            velocity: None,
+        },
+        "x = 4, y = 2\nb3o$3o!",
+    ),
+    (
+        PatternInfo {
+            name: "Beacon",
+            discoverer: Some("John Conway"),
+            category: PatternCategory::Oscillator,
+            period: Some(2),
+            velocity: None,
+        },
+        "x = 4, y = 4\n2o$o$3bo$2b2o!",
+    ),
+    (
+        PatternInfo {
+            name: "Glider",
+            discoverer: Some("Richard K. Guy"),
+            category: PatternCategory::Spaceship,
+            period: Some(4),
+            velocity: Some("c/4 diagonal"),
+        },
+        "x = 3, y = 3\nbo$2bo$3o!",
+    ),
+    (
+        PatternInfo {
+            name: "Lightweight spaceship",
+            discoverer: Some("John Conway"),
+            category: PatternCategory::Spaceship,
+            period: Some(4),
+            velocity: Some("c/2 orthogonal"),
+        },
+        "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    ),
+    (
+        PatternInfo {
+            name: "Gosper glider gun",
+            discoverer: Some("Bill Gosper"),
+            category: PatternCategory::Gun,
+            period: Some(30),
+            velocity: None,
+        },
+        "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+         2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+    (
+        PatternInfo {
+            name: "R-pentomino",
+            discoverer: Some("John Conway"),
+            category: PatternCategory::Methuselah,
+            period: None,
+            velocity: None,
+        },
+        "x = 3, y = 3\nb2o$2o$bo!",
+    ),
+];
+
+/// The metadata of every catalog pattern
+pub fn catalog() -> impl Iterator<Item = &'static PatternInfo> {
+    CATALOG.iter().map(|(info, _)| info)
+}
+
+/// Looks up the metadata of a catalog pattern by name, see [`CellPattern::by_name`] for how names are matched.
+pub fn info(name: &str) -> Option<&'static PatternInfo> {
+    let key = name_key(name);
+    catalog().find(|info| name_key(info.name) == key)
+}
+
+/// Lowercases the name and drops everything but letters and digits, so `Gosper glider gun` matches `gosper_glider_gun`
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
+
+impl CellPattern {
+    /// Looks up a catalog pattern by name, returning its metadata alongside its cells.
+    ///
+    /// Names are matched ignoring case, spaces and punctuation, so file names like `gosper_glider_gun` work too.
+    pub fn by_name(name: &str) -> Option<(&'static PatternInfo, CellPattern)> {
+        let key = name_key(name);
+        CATALOG
+            .iter()
+            .find(|(info, _)| name_key(info.name) == key)
+            .map(|(info, rle)| {
+                let pattern = CellPattern::from_rle(rle).expect("catalog patterns are valid RLE");
+                (info, pattern)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{universe::Universe, utils::Position, SimulationConfig};
+
+    #[test]
+    fn catalog_periods_are_correct() {
+        let config = SimulationConfig::default();
+        for info in catalog() {
+            let (_, pattern) = CellPattern::by_name(info.name).unwrap();
+            // Guns keep growing, so only their own cells come back
+            let period = match (info.category, info.period) {
+                (PatternCategory::Gun, _) | (_, None) => continue,
+                (_, Some(period)) => period,
+            };
+            let start = Universe::from_pattern(&pattern, None);
+            let mut universe = start.fork();
+            universe.advance(period, &config);
+            let moved = start.bounds().left != universe.bounds().left
+                || start.bounds().bottom != universe.bounds().bottom;
+            universe.translate(Position::new(
+                start.bounds().left - universe.bounds().left,
+                start.bounds().bottom - universe.bounds().bottom,
+            ));
+            assert!(universe.diff(&start).is_empty(), "{}", info.name);
+            assert_eq!(
+                moved,
+                info.category == PatternCategory::Spaceship,
+                "{}",
+                info.name
+            );
+        }
+    }
+
+    #[test]
+    fn looks_up_patterns_by_loose_names() {
+        let (info, pattern) = CellPattern::by_name("gosper_glider_gun").unwrap();
+        assert_eq!(info.discoverer, Some("Bill Gosper"));
+        assert_eq!(pattern.cells.len(), 36);
+        assert_eq!(info, super::info("Gosper Glider Gun").unwrap());
+        assert!(CellPattern::by_name("unknown").is_none());
+    }
+}
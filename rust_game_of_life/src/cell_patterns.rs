@@ -1,5 +1,8 @@
-use crate::utils::Position;
+use std::{error::Error, fmt, fs, io, path::Path};
 
+use crate::{format::Format, universe::Universe, utils::Position};
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct CellPattern {
     pub cells: Vec<Position>,
 }
@@ -16,4 +19,220 @@ impl CellPattern {
             Position::new(2, 1),
         ])
     }
+    /// Moves the pattern so that its bottom left corner is at the origin, with the cells sorted for comparisons.
+    pub fn normalized(&self) -> CellPattern {
+        let min_x = self.cells.iter().map(|pos| pos.x).min().unwrap_or_default();
+        let min_y = self.cells.iter().map(|pos| pos.y).min().unwrap_or_default();
+        let mut cells: Vec<Position> = self
+            .cells
+            .iter()
+            .map(|pos| Position::new(pos.x - min_x, pos.y - min_y))
+            .collect();
+        cells.sort();
+        CellPattern::new(cells)
+    }
+    /// Moves every cell of the pattern by `offset`, such as onto the cell under the cursor to preview where it would be placed.
+    pub fn translated(&self, offset: Position) -> CellPattern {
+        CellPattern::new(
+            self.cells
+                .iter()
+                .map(|pos| Position::new(pos.x + offset.x, pos.y + offset.y))
+                .collect(),
+        )
+    }
+    /// Turns the pattern a quarter turn clockwise, [normalized](CellPattern::normalized) so that its bottom left corner stays at the origin.
+    pub fn rotated_clockwise(&self) -> CellPattern {
+        CellPattern::new(
+            self.cells
+                .iter()
+                .map(|pos| Position::new(pos.y, -pos.x))
+                .collect(),
+        )
+        .normalized()
+    }
+    /// Flips the pattern left to right, [normalized](CellPattern::normalized) so that its bottom left corner stays at the origin.
+    pub fn mirrored(&self) -> CellPattern {
+        CellPattern::new(
+            self.cells
+                .iter()
+                .map(|pos| Position::new(-pos.x, pos.y))
+                .collect(),
+        )
+        .normalized()
+    }
+    /// Places the pattern in a new universe with `margin` dead cells on every side, without spawning any entities.
+    ///
+    /// The board covers the pattern's bounding box plus the margin, from the origin up and to the right, with the pattern in its middle.
+    /// Its rectangle is [`Universe::bounds`] padded by `margin`, ready for a [bounded](crate::topology::Topology::Bounded) or toroidal topology.
+    pub fn to_universe_centered(&self, margin: i32) -> Universe {
+        let margin = margin.max(0);
+        Universe::from_positions(
+            self.normalized()
+                .cells
+                .into_iter()
+                .map(|pos| Position::new(pos.x + margin, pos.y + margin)),
+        )
+    }
+    /// Magnifies the pattern by replacing every cell with a `factor` × `factor` block, moved so that its bottom left corner is at the origin.
+    ///
+    /// Handy for banners and for trying rules on thick features, though the result doesn't behave like the original pattern.
+    pub fn scaled(&self, factor: u32) -> CellPattern {
+        let factor = factor as i32;
+        let min_x = self.cells.iter().map(|pos| pos.x).min().unwrap_or_default();
+        let min_y = self.cells.iter().map(|pos| pos.y).min().unwrap_or_default();
+        let mut cells = Vec::with_capacity(self.cells.len() * (factor * factor) as usize);
+        for pos in &self.cells {
+            for dy in 0..factor {
+                for dx in 0..factor {
+                    cells.push(Position::new(
+                        (pos.x - min_x) * factor + dx,
+                        (pos.y - min_y) * factor + dy,
+                    ));
+                }
+            }
+        }
+        CellPattern::new(cells)
+    }
+    /// Loads a pattern from a file, picking the format from the file's extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<CellPattern, PatternError> {
+        let path = path.as_ref();
+        if !is_supported_file(path) {
+            return Err(PatternError::UnsupportedFormat(
+                path.extension()
+                    .map(|ext| ext.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ));
+        }
+        let format = match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("mc") => Format::Macrocell,
+            _ => Format::Rle,
+        };
+        CellPattern::from_reader(fs::File::open(path)?, format)
+    }
+}
+
+/// File extensions that [`CellPattern::from_file`] knows how to parse
+pub const SUPPORTED_EXTENSIONS: [&str; 2] = ["rle", "mc"];
+
+/// Whether the file at `path` has one of the [`SUPPORTED_EXTENSIONS`]
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| {
+            SUPPORTED_EXTENSIONS
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported))
+        })
+        .unwrap_or(false)
+}
+
+/// An error that occurred while loading or parsing a pattern
+#[derive(Debug)]
+pub enum PatternError {
+    Io(io::Error),
+    /// The file extension isn't one of the [`SUPPORTED_EXTENSIONS`]
+    UnsupportedFormat(String),
+    /// The pattern text is malformed, `line` is 1-based
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// A binary snapshot is malformed or truncated
+    InvalidSnapshot(String),
+}
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::Io(err) => write!(f, "failed to read pattern: {}", err),
+            PatternError::UnsupportedFormat(ext) => {
+                write!(f, "unsupported pattern format '{}'", ext)
+            }
+            PatternError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            PatternError::InvalidSnapshot(message) => write!(f, "invalid snapshot: {}", message),
+        }
+    }
+}
+impl Error for PatternError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatternError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+impl From<io::Error> for PatternError {
+    fn from(err: io::Error) -> Self {
+        PatternError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaling_magnifies_cells() {
+        let single = CellPattern::new(vec![Position::new(-4, 7)]);
+        let mut scaled = single.scaled(3).cells;
+        scaled.sort();
+        let expected: Vec<Position> = (0..3)
+            .flat_map(|x| (0..3).map(move |y| Position::new(x, y)))
+            .collect();
+        assert_eq!(scaled, expected);
+        assert_eq!(CellPattern::glider().scaled(2).cells.len(), 20);
+        assert!(CellPattern::glider().scaled(0).cells.is_empty());
+    }
+
+    #[test]
+    fn orienting_and_translating() {
+        let glider = CellPattern::glider().normalized();
+        let mut turned = glider.clone();
+        for _ in 0..4 {
+            turned = turned.rotated_clockwise();
+        }
+        assert_eq!(turned, glider);
+        assert_ne!(glider.rotated_clockwise(), glider);
+        assert_eq!(glider.mirrored().mirrored(), glider);
+        assert_eq!(
+            glider.rotated_clockwise(),
+            CellPattern::new(vec![
+                Position::new(0, 0),
+                Position::new(0, 2),
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(2, 1),
+            ])
+        );
+        let moved = glider.translated(Position::new(4, -2));
+        assert_eq!(moved.cells[0], Position::new(4, -2));
+        assert_eq!(moved.normalized(), glider);
+    }
+
+    #[test]
+    fn centering_adds_a_margin() {
+        let blinker = CellPattern::new(vec![
+            Position::new(-3, 7),
+            Position::new(-2, 7),
+            Position::new(-1, 7),
+        ]);
+        let universe = blinker.to_universe_centered(2);
+        let bounds = universe.bounds();
+        assert_eq!(
+            (bounds.left, bounds.bottom, bounds.right, bounds.top),
+            (2, 2, 4, 2)
+        );
+        let board = bounds.with_padding(2);
+        assert_eq!(
+            (board.left, board.bottom, board.right, board.top),
+            (0, 0, 6, 4)
+        );
+    }
+
+    #[test]
+    fn normalizing_moves_to_origin() {
+        let pattern = CellPattern::new(vec![Position::new(5, -2), Position::new(3, 4)]);
+        assert_eq!(
+            pattern.normalized().cells,
+            vec![Position::new(0, 6), Position::new(2, 0)]
+        );
+    }
 }
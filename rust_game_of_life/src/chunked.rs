@@ -0,0 +1,116 @@
+//! Seeded random generation that splits the board into chunks with their own random number generators.
+//!
+//! Every chunk is a band of [`CHUNK_ROWS`] rows whose generator is seeded from the master seed and the chunk's index,
+//! so chunks can be filled in any order, or in parallel behind the `parallel` feature, and still give the same cells.
+//! The cells differ from [`Universe::generate_seeded`], which draws every cell from a single generator.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    universe::{Materials, Universe},
+    utils::{Position, SizeInt},
+};
+
+/// The height of the bands the board is split into
+pub const CHUNK_ROWS: i32 = 32;
+
+impl Universe {
+    /// Randomly generates a universe that is reproducible from `seed`, filling chunks in parallel if the `parallel` feature is enabled.
+    ///
+    /// Covers the same `size` rectangle centered on the origin as [`Universe::generate_seeded`]. See the [module docs](self) for how chunks are seeded.
+    pub fn generate_chunked(
+        commands: &mut Commands,
+        materials: Materials,
+        size: SizeInt,
+        life_chance: f32,
+        seed: u64,
+    ) -> Self {
+        let mut universe = Self::random_chunked(size, life_chance, seed);
+        universe.materials = materials;
+        universe.sync_entities(commands);
+        universe
+    }
+    /// Like [`Universe::generate_chunked`], but without spawning any entities.
+    pub fn random_chunked(size: SizeInt, life_chance: f32, seed: u64) -> Self {
+        #[cfg(feature = "parallel")]
+        let positions = chunked_positions_parallel(size, life_chance, seed);
+        #[cfg(not(feature = "parallel"))]
+        let positions = chunked_positions(size, life_chance, seed);
+        Self::from_positions(positions)
+    }
+}
+
+/// Rolls every chunk one after another, see the [module docs](self)
+pub fn chunked_positions(size: SizeInt, life_chance: f32, seed: u64) -> Vec<Position> {
+    (0..chunk_count(size))
+        .flat_map(|chunk| chunk_positions(size, life_chance, seed, chunk))
+        .collect()
+}
+
+/// Rolls the chunks in parallel, giving the same positions in the same order as [`chunked_positions`]
+#[cfg(feature = "parallel")]
+pub fn chunked_positions_parallel(size: SizeInt, life_chance: f32, seed: u64) -> Vec<Position> {
+    let chunks: Vec<Vec<Position>> = (0..chunk_count(size))
+        .into_par_iter()
+        .map(|chunk| chunk_positions(size, life_chance, seed, chunk))
+        .collect();
+    chunks.concat()
+}
+
+fn chunk_count(size: SizeInt) -> u32 {
+    let rows = 2 * (size.height / 2).max(0);
+    ((rows + CHUNK_ROWS - 1) / CHUNK_ROWS) as u32
+}
+
+/// Rolls the cells of one band row by row from the bottom left, like [`Universe::generate_seeded`] does for the whole board
+fn chunk_positions(size: SizeInt, life_chance: f32, seed: u64, chunk: u32) -> Vec<Position> {
+    let half_size = SizeInt::new(size.width / 2, size.height / 2);
+    // Spread the chunk indices apart before seeding, so that neighboring master seeds don't share streams
+    let chunk_seed = seed.wrapping_add((chunk as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let mut rng = StdRng::seed_from_u64(chunk_seed);
+    let bottom = -half_size.height + chunk as i32 * CHUNK_ROWS;
+    let top = (bottom + CHUNK_ROWS).min(half_size.height);
+    let mut positions = Vec::new();
+    for y in bottom..top {
+        for x in -half_size.width..half_size.width {
+            if rng.gen::<f32>() < life_chance {
+                positions.push(Position::new(x, y));
+            }
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_generation_is_reproducible() {
+        let size = SizeInt::new(100, 150);
+        let a = chunked_positions(size, 0.3, 9);
+        assert_eq!(a, chunked_positions(size, 0.3, 9));
+        assert_ne!(a, chunked_positions(size, 0.3, 10));
+        assert!(a
+            .iter()
+            .all(|pos| (-50..50).contains(&pos.x) && (-75..75).contains(&pos.y)));
+        // Every band gets its own stream, so the cells of the last, partial band are random too
+        assert!(a.iter().any(|pos| pos.y >= 64));
+        assert_eq!(Universe::random_chunked(size, 0.3, 9).population(), a.len());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_and_serial_chunks_match() {
+        for seed in 0..5 {
+            let size = SizeInt::new(80, 200);
+            assert_eq!(
+                chunked_positions_parallel(size, 0.4, seed),
+                chunked_positions(size, 0.4, seed)
+            );
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Comparing universes, for example the same seed stepped under different rules.
+
+use crate::{
+    cell_patterns::CellPattern,
+    universe::{Bounds, CellState, Universe},
+    utils::Position,
+    SimulationConfig,
+};
+
+/// The live cells that differ between two universes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UniverseDiff {
+    /// Cells that are only alive in the universe [`Universe::diff`] was called on, sorted by position
+    pub only_in_self: Vec<Position>,
+    /// Cells that are only alive in the other universe, sorted by position
+    pub only_in_other: Vec<Position>,
+}
+impl UniverseDiff {
+    /// Whether both universes have the same live cells
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty()
+    }
+}
+
+/// The mirrors and rotations that leave a pattern's shape unchanged, see [`Universe::symmetry_group`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Symmetries {
+    /// Unchanged when flipped left to right
+    pub mirror_left_right: bool,
+    /// Unchanged when flipped top to bottom
+    pub mirror_top_bottom: bool,
+    /// Unchanged when flipped across the diagonal from the bottom left to the top right
+    pub mirror_diagonal: bool,
+    /// Unchanged when flipped across the diagonal from the top left to the bottom right
+    pub mirror_antidiagonal: bool,
+    /// Unchanged when rotated a quarter turn
+    pub rotate_90: bool,
+    /// Unchanged when rotated a half turn
+    pub rotate_180: bool,
+}
+impl Symmetries {
+    /// Every symmetry of a square, like the ones of a pulsar
+    pub const ALL: Symmetries = Symmetries {
+        mirror_left_right: true,
+        mirror_top_bottom: true,
+        mirror_diagonal: true,
+        mirror_antidiagonal: true,
+        rotate_90: true,
+        rotate_180: true,
+    };
+    /// Whether the pattern has no symmetry at all
+    pub fn is_asymmetric(&self) -> bool {
+        *self == Symmetries::default()
+    }
+}
+
+impl Universe {
+    /// Copies the live cells and their tags into a new universe without entities or history, cheap enough to fork a universe every frame.
+    ///
+    /// The fork can be stepped independently, for example under a different rule with [`Universe::step`].
+    pub fn fork(&self) -> Universe {
+        self.detached()
+    }
+    /// Finds the live cells that are only alive in one of the universes.
+    pub fn diff(&self, other: &Universe) -> UniverseDiff {
+        let only_in = |a: &Universe, b: &Universe| {
+            let mut positions: Vec<Position> = a
+                .live_positions()
+                .filter(|pos| b.state_at(*pos) == CellState::Dead)
+                .collect();
+            positions.sort();
+            positions
+        };
+        UniverseDiff {
+            only_in_self: only_in(self, other),
+            only_in_other: only_in(other, self),
+        }
+    }
+    /// The [Jaccard index](https://en.wikipedia.org/wiki/Jaccard_index) of the live cells,
+    /// from 0.0 when no cell is alive in both to 1.0 when the live cells are the same.
+    ///
+    /// Two empty universes are identical, so their similarity is 1.0.
+    pub fn similarity(&self, other: &Universe) -> f64 {
+        let shared = self
+            .live_positions()
+            .filter(|pos| other.state_at(*pos) == CellState::Alive)
+            .count();
+        let union = self.population() + other.population() - shared;
+        if union == 0 {
+            1.0
+        } else {
+            shared as f64 / union as f64
+        }
+    }
+    /// The offset that moves the live cells of `self` onto those of `other`, or `None` if `other` isn't just moved.
+    ///
+    /// Handy for spaceships, such as checking that a glider is the same shape one cell further diagonally after 4 ticks.
+    /// Two empty universes are the same at any offset, so the offset is 0.
+    pub fn equivalent_up_to_translation(&self, other: &Universe) -> Option<Position> {
+        if self.population() != other.population() {
+            return None;
+        }
+        if self.population() == 0 {
+            return Some(Position::default());
+        }
+        // Matching cell sets have matching bottom left corners
+        let (from, to) = (self.bounds(), other.bounds());
+        let offset = Position::new(to.left - from.left, to.bottom - from.bottom);
+        self.live_positions()
+            .all(|pos| {
+                other.state_at(Position::new(pos.x + offset.x, pos.y + offset.y))
+                    == CellState::Alive
+            })
+            .then_some(offset)
+    }
+    /// Finds which mirrors and rotations leave the shape of the live cells unchanged, ignoring where the shape ends up.
+    ///
+    /// An empty universe looks the same however it's turned, so it has every symmetry.
+    pub fn symmetry_group(&self) -> Symmetries {
+        let shape = self.to_pattern();
+        let has = |transform: fn(Position) -> Position| {
+            CellPattern::new(shape.cells.iter().map(|pos| transform(*pos)).collect()).normalized()
+                == shape
+        };
+        Symmetries {
+            mirror_left_right: has(|pos| Position::new(-pos.x, pos.y)),
+            mirror_top_bottom: has(|pos| Position::new(pos.x, -pos.y)),
+            mirror_diagonal: has(|pos| Position::new(pos.y, pos.x)),
+            mirror_antidiagonal: has(|pos| Position::new(-pos.y, -pos.x)),
+            rotate_90: has(|pos| Position::new(-pos.y, pos.x)),
+            rotate_180: has(|pos| Position::new(-pos.x, -pos.y)),
+        }
+    }
+    /// The shape of an oscillator at a fixed phase, so captures of the same oscillator at different phases compare equal.
+    ///
+    /// Steps a fork through the `period` phases and picks the [normalized](CellPattern::normalized) shape with the smallest sorted cells.
+    /// A `period` of 0 is treated as 1, which just normalizes the current shape.
+    pub fn canonical_phase(&self, period: u32, config: &SimulationConfig) -> CellPattern {
+        let mut phase = self.fork();
+        let mut canonical = phase.to_pattern().normalized();
+        for _ in 1..period {
+            phase.step(config);
+            let shape = phase.to_pattern().normalized();
+            if shape.cells < canonical.cells {
+                canonical = shape;
+            }
+        }
+        canonical
+    }
+    /// Whether the cells of `pattern` moved by `offset` are all alive, such as to check if a glider has reached a target yet.
+    ///
+    /// With `strict`, the rest of the pattern's bounding box also has to be dead, so the pattern isn't just part of a bigger shape.
+    /// Cells outside of the bounding box are never checked.
+    pub fn pattern_matches_at(
+        &self,
+        pattern: &CellPattern,
+        offset: Position,
+        strict: bool,
+    ) -> bool {
+        let mut bounds = Bounds::empty();
+        for pos in &pattern.cells {
+            let pos = Position::new(pos.x + offset.x, pos.y + offset.y);
+            if self.state_at(pos) == CellState::Dead {
+                return false;
+            }
+            bounds.include(pos);
+        }
+        !strict || self.count_in_region(bounds) == pattern.cells.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{rule::Rule, utils::SizeInt, SimulationConfig};
+
+    #[test]
+    fn diff_and_similarity_of_overlapping_universes() {
+        let a = Universe::from_positions([Position::new(0, 0), Position::new(1, 0)]);
+        let b = Universe::from_positions([Position::new(1, 0), Position::new(2, 0)]);
+        let diff = a.diff(&b);
+        assert_eq!(diff.only_in_self, vec![Position::new(0, 0)]);
+        assert_eq!(diff.only_in_other, vec![Position::new(2, 0)]);
+        assert!((a.similarity(&b) - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert!(a.diff(&a.fork()).is_empty());
+        assert_eq!(Universe::default().similarity(&Universe::default()), 1.0);
+    }
+
+    #[test]
+    fn gliders_are_translated_every_period() {
+        let config = SimulationConfig::default();
+        let start = Universe::from(&CellPattern::glider());
+        let mut glider = start.fork();
+        for _ in 0..3 {
+            glider.step(&config);
+            assert_eq!(start.equivalent_up_to_translation(&glider), None);
+        }
+        glider.step(&config);
+        assert_eq!(
+            start.equivalent_up_to_translation(&glider),
+            Some(Position::new(1, 1))
+        );
+        assert_eq!(
+            glider.equivalent_up_to_translation(&start),
+            Some(Position::new(-1, -1))
+        );
+        assert_eq!(
+            Universe::default().equivalent_up_to_translation(&start),
+            None
+        );
+    }
+
+    #[test]
+    fn forks_diverge_under_different_rules() {
+        let conway = SimulationConfig::default();
+        let mut high_life = SimulationConfig::default();
+        high_life.set_rule(Rule::parse("B36/S23").unwrap());
+
+        let soup = Universe::random(SizeInt::new(32, 32), 0.4, &mut StdRng::seed_from_u64(12));
+        let mut a = soup.fork();
+        let mut b = soup.fork();
+        let mut similarities = vec![];
+        for _ in 0..30 {
+            a.step(&conway);
+            b.step(&high_life);
+            similarities.push(a.similarity(&b));
+        }
+        assert!(similarities[29] < 1.0);
+        assert!(similarities
+            .iter()
+            .all(|similarity| (0.0..=1.0).contains(similarity)));
+    }
+
+    #[test]
+    fn finds_stamped_gliders() {
+        let glider = CellPattern::glider();
+        let offset = Position::new(10, 5);
+        let mut universe = Universe::from_positions(
+            glider
+                .translated(offset)
+                .cells
+                .into_iter()
+                .chain(glider.translated(Position::new(-30, 0)).cells),
+        );
+        assert!(universe.pattern_matches_at(&glider, offset, true));
+        assert!(universe.pattern_matches_at(&glider, Position::new(-30, 0), true));
+        assert!(!universe.pattern_matches_at(&glider, Position::new(11, 5), false));
+        assert!(!universe.pattern_matches_at(&glider, Position::new(0, 0), false));
+
+        // An extra cell inside the bounding box only fails the strict check
+        universe = Universe::from_positions(
+            glider
+                .translated(offset)
+                .cells
+                .into_iter()
+                .chain([Position::new(10, 7)]),
+        );
+        assert!(universe.pattern_matches_at(&glider, offset, false));
+        assert!(!universe.pattern_matches_at(&glider, offset, true));
+    }
+
+    #[test]
+    fn blinker_phases_share_a_canonical_phase() {
+        let config = SimulationConfig::default();
+        let horizontal = Universe::from_positions([
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let vertical = Universe::from_positions([
+            Position::new(5, 4),
+            Position::new(5, 5),
+            Position::new(5, 6),
+        ]);
+        assert_ne!(
+            horizontal.to_pattern().normalized(),
+            vertical.to_pattern().normalized()
+        );
+        assert_eq!(
+            horizontal.canonical_phase(2, &config),
+            vertical.canonical_phase(2, &config)
+        );
+        // The capture itself is left at its own phase
+        assert_eq!(horizontal.population(), 3);
+        assert_eq!(horizontal.bounds().top, 0);
+    }
+
+    #[test]
+    fn finds_the_symmetries_of_a_shape() {
+        let pulsar = CellPattern::from_rle(
+            "x = 13, y = 13\n2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+        )
+        .unwrap();
+        assert_eq!(pulsar.cells.len(), 48);
+        assert_eq!(Universe::from(&pulsar).symmetry_group(), Symmetries::ALL);
+
+        assert!(Universe::from(&CellPattern::glider())
+            .symmetry_group()
+            .is_asymmetric());
+
+        let blinker = Universe::from_positions([
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        assert_eq!(
+            blinker.symmetry_group(),
+            Symmetries {
+                mirror_left_right: true,
+                mirror_top_bottom: true,
+                rotate_180: true,
+                ..Symmetries::default()
+            }
+        );
+    }
+}
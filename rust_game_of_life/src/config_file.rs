@@ -0,0 +1,268 @@
+//! Loading and saving a [`SimulationConfig`] as human-editable [TOML](https://toml.io) or [RON](https://github.com/ron-rs/ron).
+//!
+//! The rule is written as a rulestring and durations as seconds, for example:
+//!
+//! ```toml
+//! rule = "B36/S23"
+//! tick_speed = 0.1
+//! render_padding = 5
+//!
+//! [generation]
+//! width = 64
+//! height = 64
+//! life_chance = 0.3
+//! seed = 42
+//! ```
+//!
+//! Missing fields keep their default values. The topology, neighborhood and neighbor weights aren't part of the file.
+
+use std::{error::Error, fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{rule::Rule, utils::SizeInt, GenerationConfig, SimulationConfig, SpeedPreset};
+
+/// Why a config file couldn't be read or written
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigFileError {
+    /// The text isn't valid TOML or RON, or doesn't have the expected fields
+    Parse(String),
+    /// The config couldn't be written in the format
+    Serialize(String),
+    /// A field has a value that doesn't make sense
+    Invalid { field: &'static str, value: String },
+}
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigFileError::Parse(message) => write!(f, "invalid config file: {}", message),
+            ConfigFileError::Serialize(message) => {
+                write!(f, "couldn't write config file: {}", message)
+            }
+            ConfigFileError::Invalid { field, value } => {
+                write!(f, "invalid value '{}' for '{}'", value, field)
+            }
+        }
+    }
+}
+impl Error for ConfigFileError {}
+
+/// The file layout of a [`SimulationConfig`]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    rule: String,
+    /// Seconds between ticks
+    tick_speed: f64,
+    speed_preset: Option<String>,
+    paused: bool,
+    #[serde(alias = "bound_padding")]
+    render_padding: i32,
+    sim_margin: i32,
+    walls_count_as_alive: bool,
+    history_depth: usize,
+    dead_decay_generations: u8,
+    auto_pause_on_stable: bool,
+    // Tables have to come after plain values in TOML
+    generation: GenerationFile,
+}
+impl Default for ConfigFile {
+    fn default() -> Self {
+        ConfigFile::from(&SimulationConfig::default())
+    }
+}
+impl From<&SimulationConfig> for ConfigFile {
+    fn from(config: &SimulationConfig) -> Self {
+        let generation = &config.generation;
+        Self {
+            rule: config.rule().to_string(),
+            tick_speed: config.tick_speed.as_secs_f64(),
+            speed_preset: config.speed_preset.map(|preset| format!("{:?}", preset)),
+            paused: config.paused,
+            render_padding: config.render_padding,
+            sim_margin: config.sim_margin,
+            walls_count_as_alive: config.walls_count_as_alive,
+            history_depth: config.history_depth,
+            dead_decay_generations: config.dead_decay_generations,
+            auto_pause_on_stable: config.auto_pause_on_stable,
+            generation: GenerationFile {
+                width: generation.initial_size.width,
+                height: generation.initial_size.height,
+                life_chance: generation.life_chance,
+                seed: generation.seed,
+                warmup_generations: generation.warmup_generations,
+            },
+        }
+    }
+}
+impl ConfigFile {
+    /// Applies the file on top of the default config.
+    fn into_config(self) -> Result<SimulationConfig, ConfigFileError> {
+        let rule = Rule::parse(&self.rule).ok_or_else(|| ConfigFileError::Invalid {
+            field: "rule",
+            value: self.rule.clone(),
+        })?;
+        // Negative, infinite and too large values don't fit in a `Duration`
+        let tick_speed =
+            Duration::try_from_secs_f64(self.tick_speed).map_err(|_| ConfigFileError::Invalid {
+                field: "tick_speed",
+                value: self.tick_speed.to_string(),
+            })?;
+        let speed_preset = match self.speed_preset {
+            Some(name) => {
+                Some(
+                    name.parse::<SpeedPreset>()
+                        .map_err(|_| ConfigFileError::Invalid {
+                            field: "speed_preset",
+                            value: name,
+                        })?,
+                )
+            }
+            None => None,
+        };
+        let mut config = SimulationConfig {
+            tick_speed,
+            speed_preset,
+            paused: self.paused,
+            render_padding: self.render_padding,
+            sim_margin: self.sim_margin,
+            walls_count_as_alive: self.walls_count_as_alive,
+            history_depth: self.history_depth,
+            dead_decay_generations: self.dead_decay_generations,
+            auto_pause_on_stable: self.auto_pause_on_stable,
+            generation: GenerationConfig {
+                initial_size: SizeInt::new(self.generation.width, self.generation.height),
+                life_chance: self.generation.life_chance,
+                seed: self.generation.seed,
+                warmup_generations: self.generation.warmup_generations,
+            },
+            ..Default::default()
+        };
+        config.set_rule(rule);
+        Ok(config)
+    }
+}
+
+/// The file layout of a [`GenerationConfig`]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct GenerationFile {
+    width: i32,
+    height: i32,
+    life_chance: f32,
+    seed: Option<u64>,
+    warmup_generations: u32,
+}
+impl Default for GenerationFile {
+    fn default() -> Self {
+        ConfigFile::default().generation
+    }
+}
+
+impl SimulationConfig {
+    /// Reads a config from TOML, see the [module docs](self) for the layout.
+    pub fn from_toml_str(input: &str) -> Result<SimulationConfig, ConfigFileError> {
+        toml::from_str::<ConfigFile>(input)
+            .map_err(|err| ConfigFileError::Parse(err.to_string()))?
+            .into_config()
+    }
+    /// Writes the config as TOML, see the [module docs](self) for the layout.
+    pub fn to_toml_str(&self) -> Result<String, ConfigFileError> {
+        toml::to_string(&ConfigFile::from(self))
+            .map_err(|err| ConfigFileError::Serialize(err.to_string()))
+    }
+    /// Reads a config from RON, with the same fields as the TOML layout.
+    pub fn from_ron_str(input: &str) -> Result<SimulationConfig, ConfigFileError> {
+        ron::from_str::<ConfigFile>(input)
+            .map_err(|err| ConfigFileError::Parse(err.to_string()))?
+            .into_config()
+    }
+    /// Writes the config as RON, with the same fields as the TOML layout.
+    pub fn to_ron_str(&self) -> Result<String, ConfigFileError> {
+        ron::ser::to_string_pretty(&ConfigFile::from(self), ron::ser::PrettyConfig::new())
+            .map_err(|err| ConfigFileError::Serialize(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_config() -> SimulationConfig {
+        let mut config = SimulationConfig {
+            tick_speed: Duration::from_millis(250),
+            speed_preset: Some(SpeedPreset::Turbo),
+            render_padding: 2,
+            sim_margin: 3,
+            history_depth: 100,
+            generation: GenerationConfig {
+                initial_size: SizeInt::new(64, 48),
+                life_chance: 0.25,
+                seed: Some(42),
+                warmup_generations: 10,
+            },
+            ..Default::default()
+        };
+        config.set_rule(Rule::parse("B36/S23").unwrap());
+        config
+    }
+
+    fn assert_same(a: &SimulationConfig, b: &SimulationConfig) {
+        assert_eq!(a.rule(), b.rule());
+        assert_eq!(a.tick_speed, b.tick_speed);
+        assert_eq!(a.speed_preset, b.speed_preset);
+        assert_eq!(a.render_padding, b.render_padding);
+        assert_eq!(a.sim_margin, b.sim_margin);
+        assert_eq!(a.history_depth, b.history_depth);
+        assert_eq!(a.dead_decay_generations, b.dead_decay_generations);
+        assert_eq!(a.generation.initial_size, b.generation.initial_size);
+        assert_eq!(a.generation.life_chance, b.generation.life_chance);
+        assert_eq!(a.generation.seed, b.generation.seed);
+        assert_eq!(
+            a.generation.warmup_generations,
+            b.generation.warmup_generations
+        );
+    }
+
+    #[test]
+    fn round_trips_through_toml_and_ron() {
+        let config = custom_config();
+        let toml = config.to_toml_str().unwrap();
+        assert!(toml.contains("rule = \"B36/S23\""));
+        assert_same(&SimulationConfig::from_toml_str(&toml).unwrap(), &config);
+
+        let ron = config.to_ron_str().unwrap();
+        assert_same(&SimulationConfig::from_ron_str(&ron).unwrap(), &config);
+    }
+
+    #[test]
+    fn missing_fields_keep_defaults() {
+        let config = SimulationConfig::from_toml_str("rule = \"B2/S\"").unwrap();
+        assert_eq!(config.rule(), Rule::seeds());
+        assert_same(
+            &SimulationConfig {
+                allowed_neighbors: vec![],
+                allowed_neighbors_for_birth: vec![2],
+                ..Default::default()
+            },
+            &config,
+        );
+        assert!(matches!(
+            SimulationConfig::from_toml_str("rule = \"B9/S\""),
+            Err(ConfigFileError::Invalid { field: "rule", .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_tick_speeds_that_dont_fit() {
+        for tick_speed in ["-1.0", "1e20", "inf"] {
+            assert!(matches!(
+                SimulationConfig::from_toml_str(&format!("tick_speed = {}", tick_speed)),
+                Err(ConfigFileError::Invalid {
+                    field: "tick_speed",
+                    ..
+                })
+            ));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Plain `x,y` coordinate lists for analysis in other tools.
+
+use crate::{
+    cell_patterns::PatternError,
+    universe::Universe,
+    utils::{CoordinateConvention, Position},
+};
+
+/// The first line of every exported CSV file
+const CSV_HEADER: &str = "x,y";
+
+impl Universe {
+    /// Writes the positions of all live cells as CSV with an `x,y` header, sorted by position so that the output is deterministic.
+    pub fn to_csv(&self) -> String {
+        self.to_csv_with(CoordinateConvention::default())
+    }
+    /// Like [`Universe::to_csv`], but with the positions converted to the given coordinate convention.
+    pub fn to_csv_with(&self, convention: CoordinateConvention) -> String {
+        let mut positions: Vec<Position> = self
+            .live_positions()
+            .map(|pos| convention.export(pos))
+            .collect();
+        positions.sort();
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        for pos in positions {
+            csv.push_str(&format!("{},{}\n", pos.x, pos.y));
+        }
+        csv
+    }
+    /// Reads live cell positions written by [`Universe::to_csv`], without spawning any entities.
+    ///
+    /// The header line is optional and blank lines are ignored.
+    pub fn from_csv(input: &str) -> Result<Universe, PatternError> {
+        Universe::from_csv_with(input, CoordinateConvention::default())
+    }
+    /// Like [`Universe::from_csv`], but for positions written in the given coordinate convention.
+    pub fn from_csv_with(
+        input: &str,
+        convention: CoordinateConvention,
+    ) -> Result<Universe, PatternError> {
+        let mut positions = vec![];
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line == CSV_HEADER) {
+                continue;
+            }
+            let error = || PatternError::Parse {
+                line: index + 1,
+                message: format!("expected 'x,y', got '{}'", line),
+            };
+            let (x, y) = line.split_once(',').ok_or_else(error)?;
+            let x = x.trim().parse().map_err(|_| error())?;
+            let y = y.trim().parse().map_err(|_| error())?;
+            positions.push(convention.import(Position::new(x, y)));
+        }
+        Ok(Universe::from_positions(positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell_patterns::CellPattern;
+
+    #[test]
+    fn round_trips_through_csv() {
+        let universe = Universe::from_positions([
+            Position::new(2, -1),
+            Position::new(-3, 4),
+            Position::new(0, 0),
+        ]);
+        let csv = universe.to_csv();
+        assert_eq!(csv, "x,y\n-3,4\n0,0\n2,-1\n");
+        assert_eq!(Universe::from_csv(&csv).unwrap().to_csv(), csv);
+    }
+
+    #[test]
+    fn y_down_mirrors_vertically() {
+        let glider = Universe::from(&CellPattern::glider());
+        let y_down = CoordinateConvention {
+            y_down: true,
+            ..Default::default()
+        };
+        let mirrored = Universe::from_positions(
+            CellPattern::glider()
+                .cells
+                .iter()
+                .map(|pos| Position::new(pos.x, -pos.y)),
+        );
+        assert_eq!(glider.to_csv_with(y_down), mirrored.to_csv());
+
+        let shifted = CoordinateConvention {
+            y_down: true,
+            origin: Position::new(4, -3),
+        };
+        let csv = glider.to_csv_with(shifted);
+        assert!(Universe::from_csv_with(&csv, shifted)
+            .unwrap()
+            .diff(&glider)
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_rows() {
+        assert!(matches!(
+            Universe::from_csv("x,y\n1,2\n3;4\n"),
+            Err(PatternError::Parse { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+//! Regular boards for seeing how a rule transforms simple structures, and for benchmarks with a predictable density.
+//!
+//! Every fixture covers `size` cells from the origin up and to the right, without spawning any entities.
+
+use crate::{
+    universe::Universe,
+    utils::{Position, SizeInt},
+};
+
+impl Universe {
+    /// Alternating live and dead cells, with a live cell at the origin
+    pub fn checkerboard(size: SizeInt) -> Self {
+        Self::fixture(size, |pos| (pos.x + pos.y) % 2 == 0)
+    }
+    /// Vertical lines of live cells every `period` columns, starting at the left edge.
+    ///
+    /// A period of 1 fills the board and a period of 0 leaves it empty.
+    pub fn stripes(size: SizeInt, period: u32) -> Self {
+        let columns: Vec<i32> = match period {
+            0 => vec![],
+            period => (0..size.width).step_by(period as usize).collect(),
+        };
+        Self::fixture(size, |pos| columns.contains(&pos.x))
+    }
+    /// Every cell alive
+    pub fn full(size: SizeInt) -> Self {
+        Self::fixture(size, |_| true)
+    }
+    /// A one cell wide frame around the edges of the board
+    pub fn border(size: SizeInt) -> Self {
+        Self::fixture(size, |pos| {
+            pos.x == 0 || pos.y == 0 || pos.x == size.width - 1 || pos.y == size.height - 1
+        })
+    }
+    fn fixture(size: SizeInt, alive: impl Fn(Position) -> bool) -> Self {
+        Self::from_positions(
+            (0..size.height)
+                .flat_map(|y| (0..size.width).map(move |x| Position::new(x, y)))
+                .filter(|pos| alive(*pos)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationConfig;
+
+    #[test]
+    fn full_squares_keep_only_their_corners() {
+        let mut full = Universe::full(SizeInt::new(3, 3));
+        assert_eq!(full.population(), 9);
+        // Corners have 3 neighbors and survive, the center is overcrowded with 8
+        full.step(&SimulationConfig::default());
+        for corner in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+            assert!(full.cells.contains_key(&Position::new(corner.0, corner.1)));
+        }
+        assert!(!full.cells.contains_key(&Position::new(1, 1)));
+    }
+
+    #[test]
+    fn fixtures_have_predictable_populations() {
+        let size = SizeInt::new(6, 4);
+        assert_eq!(Universe::checkerboard(size).population(), 12);
+        assert_eq!(Universe::stripes(size, 2).population(), 12);
+        assert_eq!(Universe::stripes(size, 4).population(), 8);
+        assert_eq!(Universe::stripes(size, 0).population(), 0);
+        assert_eq!(Universe::border(size).population(), 16);
+        assert_eq!(Universe::full(SizeInt::new(0, 5)).population(), 0);
+    }
+}
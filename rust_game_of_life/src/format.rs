@@ -0,0 +1,156 @@
+//! Reading and writing patterns through [`io::Read`] and [`io::Write`], for pipes, sockets and files too big to load at once.
+//!
+//! A reader has no file name to guess the format from, so the [`Format`] is always given explicitly.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use crate::{
+    cell_patterns::{CellPattern, PatternError},
+    rle::{parse_rle_lines, write_rle},
+    universe::Universe,
+    utils::CoordinateConvention,
+};
+
+/// A pattern format that can be read with [`CellPattern::from_reader`] and written with [`Universe::to_writer`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// [RLE](crate::rle), read one line at a time
+    Rle,
+    /// The [macrocell](crate::macrocell) format
+    Macrocell,
+    /// `x,y` coordinate lists, see [`Universe::to_csv`]
+    Csv,
+    /// Binary [snapshots](crate::snapshot)
+    Snapshot,
+}
+
+impl CellPattern {
+    /// Reads a pattern in the given format from `reader`.
+    ///
+    /// RLE is parsed line by line, so the whole text is never held in memory. The other formats are read to the end first.
+    pub fn from_reader(reader: impl Read, format: Format) -> Result<CellPattern, PatternError> {
+        let mut reader = BufReader::new(reader);
+        match format {
+            Format::Rle => {
+                let mut cells = vec![];
+                parse_rle_lines(reader.lines(), |pos| cells.push(pos))?;
+                Ok(CellPattern::new(cells))
+            }
+            Format::Macrocell => CellPattern::from_macrocell(&read_text(&mut reader)?),
+            Format::Csv => {
+                Universe::from_csv(&read_text(&mut reader)?).map(|universe| universe.to_pattern())
+            }
+            Format::Snapshot => {
+                let mut bytes = vec![];
+                reader.read_to_end(&mut bytes)?;
+                Universe::from_bytes(&bytes).map(|universe| universe.to_pattern())
+            }
+        }
+    }
+}
+
+impl Universe {
+    /// Writes the live cells to `writer` in the given format.
+    ///
+    /// RLE is written as it's encoded, the other formats are encoded in memory first.
+    pub fn to_writer(&self, mut writer: impl Write, format: Format) -> io::Result<()> {
+        match format {
+            Format::Rle => write_rle(self, writer),
+            Format::Macrocell => writer.write_all(self.to_macrocell().as_bytes()),
+            Format::Csv => writer.write_all(self.to_csv().as_bytes()),
+            Format::Snapshot => writer.write_all(&self.to_bytes()),
+        }
+    }
+    /// Like [`Universe::to_writer`], but with the positions converted to the given coordinate convention.
+    pub fn to_writer_with(
+        &self,
+        writer: impl Write,
+        format: Format,
+        convention: CoordinateConvention,
+    ) -> io::Result<()> {
+        self.exported(convention).to_writer(writer, format)
+    }
+}
+
+fn read_text(reader: &mut impl Read) -> io::Result<String> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::utils::{Position, SizeInt};
+
+    #[test]
+    fn round_trips_through_memory() {
+        let soup = Universe::random(SizeInt::new(90, 12), 0.4, &mut StdRng::seed_from_u64(4));
+        let expected = soup.to_pattern().normalized();
+        for format in [
+            Format::Rle,
+            Format::Macrocell,
+            Format::Csv,
+            Format::Snapshot,
+        ] {
+            let mut buffer = Cursor::new(vec![]);
+            soup.to_writer(&mut buffer, format).unwrap();
+            buffer.set_position(0);
+            let pattern = CellPattern::from_reader(buffer, format).unwrap();
+            assert_eq!(pattern.normalized(), expected, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn y_down_mirrors_every_format() {
+        let glider = Universe::from(&CellPattern::glider());
+        let y_down = CoordinateConvention {
+            y_down: true,
+            ..Default::default()
+        };
+        let expected = CellPattern::new(
+            CellPattern::glider()
+                .cells
+                .iter()
+                .map(|pos| Position::new(pos.x, -pos.y))
+                .collect(),
+        )
+        .normalized();
+        for format in [
+            Format::Rle,
+            Format::Macrocell,
+            Format::Csv,
+            Format::Snapshot,
+        ] {
+            let mut buffer = vec![];
+            glider.to_writer_with(&mut buffer, format, y_down).unwrap();
+            let pattern = CellPattern::from_reader(buffer.as_slice(), format).unwrap();
+            assert_eq!(pattern.normalized(), expected, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn writes_rle_with_merged_rows() {
+        let universe = Universe::from_positions([
+            Position::new(0, 3),
+            Position::new(2, 3),
+            Position::new(3, 3),
+            Position::new(1, 0),
+        ]);
+        let mut rle = vec![];
+        universe.to_writer(&mut rle, Format::Rle).unwrap();
+        assert_eq!(String::from_utf8(rle).unwrap(), "x = 4, y = 4\nob2o3$bo!\n");
+        let mut empty = vec![];
+        Universe::default()
+            .to_writer(&mut empty, Format::Rle)
+            .unwrap();
+        assert!(CellPattern::from_reader(empty.as_slice(), Format::Rle)
+            .unwrap()
+            .cells
+            .is_empty());
+    }
+}
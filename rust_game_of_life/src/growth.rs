@@ -0,0 +1,138 @@
+//! Classifying how fast patterns grow from the area of their bounding box, to tell still lifes from guns and puffers.
+
+use crate::{universe::Universe, SimulationConfig};
+
+/// How the bounding box area of a pattern changes over time, see [`classify_growth`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthClass {
+    /// The area stops growing, like still lifes, oscillators and spaceships
+    Stable,
+    /// The area grows at a steady rate, like a pattern that only extends in one direction
+    Linear,
+    /// The growth keeps speeding up about as fast as a square's area, like a gun whose stream extends diagonally
+    Quadratic,
+    /// The growth speeds up even faster than quadratic
+    Unbounded,
+}
+
+/// Records the bounding box area once per generation and classifies the trend
+#[derive(Clone, Debug, Default)]
+pub struct GrowthTracker {
+    /// The area of every observed generation, oldest first
+    pub areas: Vec<u64>,
+}
+impl GrowthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Records the current bounding box area of `universe`
+    pub fn observe(&mut self, universe: &Universe) {
+        self.areas.push(universe.bounding_box_area());
+    }
+    /// Classifies the areas observed so far, see [`classify_growth`]
+    pub fn classify(&self) -> GrowthClass {
+        classify_growth(&self.areas)
+    }
+}
+
+impl Universe {
+    /// The number of cells in the bounding box of the live cells, 0 for an empty universe
+    pub fn bounding_box_area(&self) -> u64 {
+        let bounds = self.bounds();
+        if bounds.is_empty() {
+            return 0;
+        }
+        (bounds.right - bounds.left + 1) as u64 * (bounds.top - bounds.bottom + 1) as u64
+    }
+    /// Simulates `generations` generations without spawning entities and classifies the growth over them, see [`classify_growth`].
+    pub fn classify_growth(&mut self, config: &SimulationConfig, generations: u32) -> GrowthClass {
+        let mut tracker = GrowthTracker::new();
+        tracker.observe(self);
+        for _ in 0..generations {
+            self.step(config);
+            tracker.observe(self);
+        }
+        tracker.classify()
+    }
+}
+
+/// Classifies a series of bounding box areas, one per generation, with a simple heuristic.
+///
+/// The series is split into thirds. If the last third never exceeds the largest earlier area, the pattern is [`GrowthClass::Stable`].
+/// Otherwise the average growth per generation of the last third is compared to the middle third:
+/// a steady rate is linear, while the rate of a quadratic area keeps rising, by roughly 1.7× between thirds of a run that starts near zero.
+/// Ratios up to 1.25 count as linear, up to 2.5 as quadratic and anything above as unbounded.
+///
+/// Growth has to have settled into its trend during the window, so use a few hundred generations for guns, whose streams take a while to leave the gun.
+/// Fewer than 3 areas are always stable.
+pub fn classify_growth(areas: &[u64]) -> GrowthClass {
+    let len = areas.len();
+    if len < 3 {
+        return GrowthClass::Stable;
+    }
+    let third = len / 3;
+    let earlier_max = areas[..len - third]
+        .iter()
+        .max()
+        .copied()
+        .unwrap_or_default();
+    let later_max = areas[len - third..]
+        .iter()
+        .max()
+        .copied()
+        .unwrap_or_default();
+    if later_max <= earlier_max {
+        return GrowthClass::Stable;
+    }
+    let rate =
+        |from: usize, to: usize| (areas[to] as f64 - areas[from] as f64) / (to - from) as f64;
+    let middle = rate(third, 2 * third);
+    let last = rate(2 * third, len - 1);
+    if middle <= 0.0 {
+        // Growth only started in the last third, too late to see a trend
+        return GrowthClass::Linear;
+    }
+    match last / middle {
+        ratio if ratio <= 1.25 => GrowthClass::Linear,
+        ratio if ratio <= 2.5 => GrowthClass::Quadratic,
+        _ => GrowthClass::Unbounded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_patterns::CellPattern, utils::Position};
+
+    #[test]
+    fn blocks_are_stable_and_guns_grow() {
+        let config = SimulationConfig::default();
+        let mut block = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(0, 1),
+            Position::new(1, 1),
+        ]);
+        assert_eq!(block.bounding_box_area(), 4);
+        assert_eq!(block.classify_growth(&config, 50), GrowthClass::Stable);
+
+        let mut glider = Universe::from(&CellPattern::glider());
+        assert_eq!(glider.classify_growth(&config, 100), GrowthClass::Stable);
+
+        let (_, gun) = CellPattern::by_name("Gosper glider gun").unwrap();
+        let mut gun = Universe::from(&gun);
+        assert_eq!(gun.classify_growth(&config, 600), GrowthClass::Quadratic);
+    }
+
+    #[test]
+    fn classifies_synthetic_trends() {
+        let linear: Vec<u64> = (0..90).map(|t| 10 + 3 * t).collect();
+        assert_eq!(classify_growth(&linear), GrowthClass::Linear);
+        let quadratic: Vec<u64> = (0..90).map(|t| t * t).collect();
+        assert_eq!(classify_growth(&quadratic), GrowthClass::Quadratic);
+        let exponential: Vec<u64> = (0..90).map(|t| 1 << (t / 3)).collect();
+        assert_eq!(classify_growth(&exponential), GrowthClass::Unbounded);
+        assert_eq!(classify_growth(&[5, 4, 5, 4, 5, 4]), GrowthClass::Stable);
+        assert_eq!(Universe::default().bounding_box_area(), 0);
+    }
+}
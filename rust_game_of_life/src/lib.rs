@@ -6,69 +6,526 @@
 //! ## Features
 //!
 //! - Infinite universe
-//! - Randomly generated universe
-//! - Custom cell patterns and presets
+//! - Randomly generated universe, including apgsearch-style 16×16 soups
+//! - Custom cell patterns and presets, with a catalog of well-known patterns
+//! - Reading and writing [RLE](https://conwaylife.com/wiki/Run_Length_Encoded) patterns with their name, author and comments, streamed line by line from any reader
+//! - Reading and writing Golly's [macrocell](https://conwaylife.com/wiki/Macrocell) format
+//! - Compact binary snapshots of universes
+//! - Capturing frames for animations at any number of generations per frame
+//! - Tiny replay files that reproduce a run from its seed and edits
+//! - Pausing automatically once the universe settles down
+//! - Classifying patterns by how fast their bounding box grows
+//! - Running until a condition on the universe is met
+//! - Named, reproducible stress scenarios for benchmarks
 //! - Simulation configuration for things like:
 //!     - Tick speed
 //!     - Neighbor count required for a cell to be alive/born
 //!     - Initial size of randomly generated universes (padding can be added)
 //!     - Chance for cell to be alive when generating the universe
+//!     - Infinite, bounded or toroidal topology
+//!     - Square or hexagonal neighborhoods
+//! - A minimal terminal renderer behind the `tui` feature
+//! - Async streaming of frames behind the `async` feature
+//! - TOML and RON config files behind the `config-file` feature
+//! - Reproducible multithreaded generation of huge boards behind the `parallel` feature
 
-use std::time::Duration;
+use std::{error::Error, fmt, str::FromStr, time::Duration};
 
-use utils::SizeInt;
+use rand::{rngs::StdRng, SeedableRng};
 
+use rule::Rule;
+use topology::Topology;
+use universe::{Bounds, Universe};
+use utils::{InvalidSize, NeighborWeights, Neighborhood, SizeInt};
+
+pub mod catalog;
 pub mod cell_patterns;
+pub mod chunked;
+pub mod compare;
+#[cfg(feature = "config-file")]
+pub mod config_file;
+pub mod csv;
+pub mod fixtures;
+pub mod format;
+pub mod growth;
+pub mod macrocell;
+pub mod neighbor_cache;
+pub mod pattern_library;
+pub mod placement;
+pub mod recorder;
+pub mod replay;
+pub mod rle;
+pub mod rule;
+pub mod run;
+pub mod search;
+pub mod snapshot;
+pub mod soup;
+pub mod stability;
+pub mod stats;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod stress;
+pub mod svg;
+pub mod topology;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod universe;
 pub mod utils;
 
 /// Controls various settings related to the simulation and generation of cells
+#[derive(Clone)]
 pub struct SimulationConfig {
-    /// Extra padding added to the universe's bounds
-    pub bound_padding: i32,
-    /// How often the universe updates
+    /// Dead cells shown around the live cells' bounds, see [`SimulationConfig::render_bounds`].
+    ///
+    /// Only changes what is drawn and how big cells appear, never how the universe evolves.
+    pub render_padding: i32,
+    /// Dead cells reserved around the live cells when confining the simulation to them with [`SimulationConfig::enclose`].
+    ///
+    /// Cells can be born in the margin, but never beyond it. Nothing reads the margin except [`SimulationConfig::enclose`],
+    /// which has to be called explicitly, such as right after generating the universe. Until then the topology is used as configured.
+    pub sim_margin: i32,
+    /// How often the universe updates, unless overridden by `speed_preset`
     pub tick_speed: Duration,
+    /// A named speed that overrides `tick_speed` if set
+    pub speed_preset: Option<SpeedPreset>,
     pub paused: bool,
     /// How many neighbors a cell can live with
     pub allowed_neighbors: Vec<u8>,
     /// How many neighbors are required for a dead cell to become a live cell, as if by reproduction
     pub allowed_neighbors_for_birth: Vec<u8>,
     pub generation: GenerationConfig,
+    /// The shape of the space the simulation runs in
+    pub topology: Topology,
+    /// Which cells around a cell count as its neighbors
+    pub neighborhood: Neighborhood,
+    /// Weighted neighbors that replace `neighborhood` if set.
+    ///
+    /// Sums below 0 count as 0 and sums above 255 as 255 when compared against the rule.
+    pub neighbor_weights: Option<NeighborWeights>,
+    /// Whether walls count as live neighbors, see [`Universe::add_walls`](universe::Universe::add_walls)
+    pub walls_count_as_alive: bool,
+    /// How many ticks are remembered for stepping back, 0 disables the history
+    pub history_depth: usize,
+    /// How many generations dead cells keep decaying for, see [`Universe::dead_decay`]. 0 disables the decay.
+    pub dead_decay_generations: u8,
+    /// Whether to pause once the universe dies, stops changing or starts repeating itself, see [`stability`]
+    pub auto_pause_on_stable: bool,
+}
+impl SimulationConfig {
+    /// Checks that the config can be simulated.
+    ///
+    /// Rules where cells are born without any live neighbors (`B0`) bring every dead cell to life at once,
+    /// so they're only allowed in a finite topology, whose background flips instead, see [`Universe::background`].
+    /// Flipping also needs neighbor counts that can be complemented, see [`SimulationConfig::full_neighbor_count`].
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.allowed_neighbors_for_birth.contains(&0) {
+            if self.topology == Topology::Infinite {
+                return Err(ConfigError::BirthWithoutNeighborsInInfiniteTopology);
+            }
+            if self.full_neighbor_count().is_none() {
+                return Err(ConfigError::UninvertibleWeights);
+            }
+        }
+        let size = self.generation.initial_size;
+        SizeInt::try_new(size.width, size.height).map_err(ConfigError::InitialSize)?;
+        Ok(())
+    }
+    /// Whether a live cell with `live_neighbors` live neighbors stays alive, never if `allowed_neighbors` is empty
+    pub fn survives(&self, live_neighbors: u8) -> bool {
+        self.allowed_neighbors.contains(&live_neighbors)
+    }
+    /// Whether a dead cell with `live_neighbors` live neighbors is born, never if `allowed_neighbors_for_birth` is empty
+    pub fn is_born(&self, live_neighbors: u8) -> bool {
+        self.allowed_neighbors_for_birth.contains(&live_neighbors)
+    }
+    /// The neighbor count of a cell whose neighbors are all alive.
+    ///
+    /// `None` if negative weights or weights adding up to more than 255 keep the counts from being complemented.
+    pub fn full_neighbor_count(&self) -> Option<u8> {
+        match &self.neighbor_weights {
+            None => u8::try_from(self.neighborhood.offsets().len()).ok(),
+            Some(weights) if weights.weights.iter().any(|(_, weight)| *weight < 0) => None,
+            Some(weights) => u8::try_from(
+                weights
+                    .weights
+                    .iter()
+                    .map(|(_, weight)| *weight)
+                    .sum::<i32>(),
+            )
+            .ok(),
+        }
+    }
+    /// The birth and survival conditions as a [`Rule`]
+    pub fn rule(&self) -> Rule {
+        Rule::new(
+            self.allowed_neighbors_for_birth.clone(),
+            self.allowed_neighbors.clone(),
+        )
+    }
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.allowed_neighbors_for_birth = rule.birth;
+        self.allowed_neighbors = rule.survival;
+    }
+    /// The rule in B/S notation, such as `B3/S23`, or `None` if it can't be summarized that way.
+    ///
+    /// Rules on a [`Neighborhood::Hex`] get an `H` suffix like in [Golly](https://golly.sourceforge.net/Help/Algorithms/QuickLife.html),
+    /// and are `None` if they use neighbor counts above 6. UIs should show something else for `None` instead of a misleading label.
+    pub fn as_rulestring(&self) -> Option<String> {
+        let rule = self.rule();
+        match self.neighborhood {
+            Neighborhood::Moore => Some(rule.to_string()),
+            Neighborhood::Hex => {
+                let fits = rule
+                    .birth
+                    .iter()
+                    .chain(&rule.survival)
+                    .all(|count| *count <= 6);
+                fits.then(|| format!("{}H", rule))
+            }
+        }
+    }
+    /// The region to draw for `universe`, its bounds grown by [`SimulationConfig::render_padding`]
+    pub fn render_bounds(&self, universe: &Universe) -> Bounds {
+        universe.bounds().with_padding(self.render_padding)
+    }
+    /// Confines the simulation to the bounds of `universe` grown by [`SimulationConfig::sim_margin`].
+    ///
+    /// A finite topology keeps its out-of-bounds policy and an infinite one becomes [`Topology::Bounded`].
+    /// Neither the app nor the runners call this on their own, so call it once the starting cells are in place.
+    /// An empty universe has nothing to enclose, so the topology is left unchanged.
+    pub fn enclose(&mut self, universe: &Universe) {
+        if universe.bounds().is_empty() {
+            return;
+        }
+        let bounds = universe.bounds().with_padding(self.sim_margin);
+        self.topology = match self.topology {
+            Topology::Infinite | Topology::Bounded(_) => Topology::Bounded(bounds),
+            Topology::Toroidal(_) => Topology::Toroidal(bounds),
+            Topology::Region(_, policy) => Topology::Region(bounds, policy),
+        };
+    }
+    /// The effective time between ticks, `None` if the universe should tick every frame
+    pub fn tick_interval(&self) -> Option<Duration> {
+        match self.speed_preset {
+            Some(preset) => preset.tick_interval(),
+            None => Some(self.tick_speed),
+        }
+    }
+}
+/// Why a [`SimulationConfig`] can't be simulated, see [`SimulationConfig::validate`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The rule gives birth to cells without live neighbors, which would fill the infinite plane in a single tick
+    BirthWithoutNeighborsInInfiniteTopology,
+    /// The rule has `B0`, but the neighbor weights can't be complemented, see [`SimulationConfig::full_neighbor_count`]
+    UninvertibleWeights,
+    /// [`GenerationConfig::initial_size`] is empty or has more cells than can be counted
+    InitialSize(InvalidSize),
+}
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::BirthWithoutNeighborsInInfiniteTopology => write!(
+                f,
+                "rules with B0 need a bounded or toroidal topology, the infinite plane would fill up at once"
+            ),
+            ConfigError::UninvertibleWeights => write!(
+                f,
+                "rules with B0 need non-negative neighbor weights that add up to at most 255"
+            ),
+            ConfigError::InitialSize(err) => write!(f, "invalid initial size: {}", err),
+        }
+    }
 }
+impl Error for ConfigError {}
+
 impl Default for SimulationConfig {
     fn default() -> Self {
         Self {
-            bound_padding: 5,
+            render_padding: 5,
+            sim_margin: 0,
             tick_speed: Duration::from_secs_f32(0.5),
+            speed_preset: None,
             paused: false,
             allowed_neighbors: vec![2, 3],
             allowed_neighbors_for_birth: vec![3],
             generation: GenerationConfig::default(),
+            topology: Topology::default(),
+            neighborhood: Neighborhood::default(),
+            neighbor_weights: None,
+            walls_count_as_alive: false,
+            history_depth: 0,
+            dead_decay_generations: 0,
+            auto_pause_on_stable: false,
+        }
+    }
+}
+
+/// Named simulation speeds that can be cycled through at runtime
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpeedPreset {
+    /// Ticks every 0.5 seconds
+    Slow,
+    /// Ticks every 0.1 seconds
+    Normal,
+    /// Ticks every 0.05 seconds
+    Fast,
+    /// Ticks every 0.01 seconds
+    Turbo,
+    /// Ticks as fast as the frame rate allows
+    Max,
+}
+impl SpeedPreset {
+    /// All presets from slowest to fastest
+    pub const ALL: [SpeedPreset; 5] = [
+        SpeedPreset::Slow,
+        SpeedPreset::Normal,
+        SpeedPreset::Fast,
+        SpeedPreset::Turbo,
+        SpeedPreset::Max,
+    ];
+    /// The time between ticks, `None` for [`SpeedPreset::Max`] which ticks every frame
+    pub fn tick_interval(&self) -> Option<Duration> {
+        match self {
+            SpeedPreset::Slow => Some(Duration::from_millis(500)),
+            SpeedPreset::Normal => Some(Duration::from_millis(100)),
+            SpeedPreset::Fast => Some(Duration::from_millis(50)),
+            SpeedPreset::Turbo => Some(Duration::from_millis(10)),
+            SpeedPreset::Max => None,
         }
     }
+    /// The next faster preset, wrapping around from [`SpeedPreset::Max`] to [`SpeedPreset::Slow`]
+    pub fn next(&self) -> SpeedPreset {
+        let index = SpeedPreset::ALL
+            .iter()
+            .position(|preset| preset == self)
+            .unwrap_or_default();
+        SpeedPreset::ALL[(index + 1) % SpeedPreset::ALL.len()]
+    }
+}
+impl FromStr for SpeedPreset {
+    type Err = String;
+
+    /// Parses a preset from its name, ignoring case
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        SpeedPreset::ALL
+            .iter()
+            .find(|preset| format!("{:?}", preset).eq_ignore_ascii_case(name))
+            .copied()
+            .ok_or_else(|| format!("unknown speed preset '{}'", name))
+    }
 }
 
 /// Configuration for universe generation
+#[derive(Clone)]
 pub struct GenerationConfig {
     /// The initial size of the universe
     pub initial_size: SizeInt,
     /// How likely it is for a cell to be alive when generating the universe, a number between 0.0 - 1.0
     pub life_chance: f32,
+    /// Seed for the random number generator, a random seed is used if `None`
+    pub seed: Option<u64>,
+    /// How many generations are simulated silently before the universe is shown
+    pub warmup_generations: u32,
+}
+impl GenerationConfig {
+    /// Creates the random number generator that all generation steps should draw from.
+    ///
+    /// A run is reproducible from [`GenerationConfig::seed`] because this is the only source of randomness:
+    /// the rules themselves are deterministic, so every draw happens while generating the universe.
+    /// Cells are rolled once each, row by row from the bottom left of [`GenerationConfig::initial_size`],
+    /// and this order is kept stable so that a seed produces the same universe across versions.
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
 }
 impl Default for GenerationConfig {
     fn default() -> Self {
         Self {
             initial_size: SizeInt::new(32, 32),
             life_chance: 0.4,
+            seed: None,
+            warmup_generations: 0,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::{
+        cell_patterns::CellPattern,
+        utils::{Position, SizeFloat},
+    };
+
+    #[test]
+    fn render_padding_only_changes_rendering() {
+        let tight = SimulationConfig {
+            render_padding: 0,
+            ..Default::default()
+        };
+        let padded = SimulationConfig {
+            render_padding: 10,
+            ..Default::default()
+        };
+        let mut a = Universe::from(&CellPattern::glider());
+        let mut b = Universe::from(&CellPattern::glider());
+        let viewport = SizeFloat::new(800.0, 600.0);
+        let (tight_scale, _) = tight.render_bounds(&a).fit_into(viewport);
+        let (padded_scale, _) = padded.render_bounds(&b).fit_into(viewport);
+        assert!(padded_scale < tight_scale);
+        for _ in 0..20 {
+            assert_eq!(a.step(&tight), b.step(&padded));
+        }
+    }
+
+    #[test]
+    fn sim_margin_leaves_room_to_grow() {
+        // A blinker needs a row above and below to flip, without them it dies out
+        let blinker = || {
+            Universe::from_positions(vec![
+                utils::Position::new(0, 0),
+                utils::Position::new(1, 0),
+                utils::Position::new(2, 0),
+            ])
+        };
+        let mut config = SimulationConfig::default();
+        config.enclose(&blinker());
+        let mut cramped = blinker();
+        cramped.advance(2, &config);
+        assert_eq!(cramped.population(), 0);
+
+        config.sim_margin = 1;
+        config.enclose(&blinker());
+        let mut roomy = blinker();
+        roomy.advance(2, &config);
+        assert_eq!(roomy.population(), 3);
+
+        let mut torus = SimulationConfig {
+            topology: Topology::Toroidal(blinker().bounds()),
+            ..Default::default()
+        };
+        torus.enclose(&Universe::default());
+        assert_eq!(torus.topology, Topology::Toroidal(blinker().bounds()));
+    }
+
+    #[test]
+    fn empty_conditions_never_match() {
+        let config = SimulationConfig {
+            allowed_neighbors: vec![],
+            allowed_neighbors_for_birth: vec![],
+            ..Default::default()
+        };
+        assert!((0..=8).all(|count| !config.survives(count) && !config.is_born(count)));
+
+        let conway = SimulationConfig::default();
+        assert!(conway.survives(2) && conway.survives(3) && !conway.survives(4));
+        assert!(conway.is_born(3) && !conway.is_born(2));
+    }
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn speed_presets_map_to_documented_intervals() {
+        let intervals: Vec<Option<Duration>> = SpeedPreset::ALL
+            .iter()
+            .map(|preset| preset.tick_interval())
+            .collect();
+        assert_eq!(
+            intervals,
+            vec![
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(50)),
+                Some(Duration::from_millis(10)),
+                None,
+            ]
+        );
+        assert_eq!(SpeedPreset::Max.next(), SpeedPreset::Slow);
+        assert_eq!("turbo".parse(), Ok(SpeedPreset::Turbo));
+
+        let config = SimulationConfig {
+            speed_preset: Some(SpeedPreset::Fast),
+            ..Default::default()
+        };
+        assert_eq!(config.tick_interval(), Some(Duration::from_millis(50)));
+        assert_eq!(
+            SimulationConfig::default().tick_interval(),
+            Some(SimulationConfig::default().tick_speed)
+        );
+    }
+
+    #[test]
+    fn only_summarizable_rules_have_rulestrings() {
+        let mut config = SimulationConfig::default();
+        assert_eq!(config.as_rulestring(), Some(String::from("B3/S23")));
+        config.neighborhood = Neighborhood::Hex;
+        config.set_rule(Rule::parse("B2/S34").unwrap());
+        assert_eq!(config.as_rulestring(), Some(String::from("B2/S34H")));
+        // A hexagonal cell can't have 8 neighbors
+        config.set_rule(Rule::parse("B2/S348").unwrap());
+        assert_eq!(config.as_rulestring(), None);
+    }
+
+    #[test]
+    fn b0_rules_need_a_finite_topology() {
+        let mut config = SimulationConfig::default();
+        assert_eq!(config.validate(), Ok(()));
+        config.set_rule(Rule::parse("B0123478/S34678").unwrap());
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::BirthWithoutNeighborsInInfiniteTopology)
+        );
+        config.topology = Topology::Bounded(universe::Bounds {
+            top: 9,
+            right: 9,
+            bottom: 0,
+            left: 0,
+        });
+        assert_eq!(config.validate(), Ok(()));
+        config.neighbor_weights = Some(NeighborWeights::new(vec![
+            (Position::new(1, 0), 2),
+            (Position::new(-1, 0), -1),
+        ]));
+        assert_eq!(config.validate(), Err(ConfigError::UninvertibleWeights));
+        config.neighbor_weights = None;
+
+        config.generation.initial_size = SizeInt::new(100_000, 100_000);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InitialSize(InvalidSize::TooLarge(_)))
+        ));
+    }
+
+    #[test]
+    fn seeded_runs_are_identical_frame_for_frame() {
+        let config = SimulationConfig {
+            generation: GenerationConfig {
+                seed: Some(2021),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let new_universe = || {
+            let generation = &config.generation;
+            universe::Universe::random(
+                generation.initial_size,
+                generation.life_chance,
+                &mut generation.rng(),
+            )
+        };
+        let mut first = new_universe();
+        let mut second = new_universe();
+        for _ in 0..50 {
+            assert_eq!(first.step(&config), second.step(&config));
+            assert_eq!(first.to_string(), second.to_string());
+        }
+    }
 }
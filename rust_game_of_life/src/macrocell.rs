@@ -0,0 +1,358 @@
+//! Reading and writing Golly's [macrocell](https://conwaylife.com/wiki/Macrocell) format, which stores a pattern as a quadtree with shared nodes.
+//!
+//! Repetitive patterns compress far better than in RLE, since every distinct square is written once.
+//! The root square is centered on the origin like in Golly, with y flipped to the crate's y-up coordinates.
+
+use std::{collections::HashMap, fmt::Write as _};
+
+use crate::{
+    cell_patterns::{CellPattern, PatternError},
+    universe::Universe,
+    utils::{CoordinateConvention, Position},
+};
+
+/// The first line of every macrocell file
+const MACROCELL_HEADER: &str = "[M2]";
+/// The level of the 8×8 leaf squares, which are written as text
+const LEAF_LEVEL: u32 = 3;
+/// The width and height of a leaf square
+const LEAF_SIZE: i32 = 1 << LEAF_LEVEL;
+/// The level of the root square that holds every `i32` position, the biggest level that is read or written
+const MAX_LEVEL: u32 = 32;
+
+impl Universe {
+    /// Writes the live cells in the macrocell format.
+    ///
+    /// Identical squares are only written once, so the output stays small for huge repetitive patterns.
+    /// No rule line is written, so Golly opens the pattern with Conway's rule.
+    pub fn to_macrocell(&self) -> String {
+        let positions: Vec<Position> = self.live_positions().collect();
+        let mut writer = MacrocellWriter {
+            output: format!("{}\n", MACROCELL_HEADER),
+            ids: HashMap::new(),
+        };
+        if !positions.is_empty() {
+            let level = root_level(&positions);
+            let half = 1i64 << (level - 1);
+            writer.write_square(positions, (-half, -half), level);
+        }
+        writer.output
+    }
+    /// Like [`Universe::to_macrocell`], but with the positions converted to the given coordinate convention.
+    pub fn to_macrocell_with(&self, convention: CoordinateConvention) -> String {
+        self.exported(convention).to_macrocell()
+    }
+}
+
+impl CellPattern {
+    /// Parses a pattern from macrocell text, see [`Universe::to_macrocell`].
+    ///
+    /// Every shared square is expanded into its cells, so the pattern can be far bigger than the text.
+    pub fn from_macrocell(input: &str) -> Result<CellPattern, PatternError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()));
+        match lines.next() {
+            Some((_, header)) if header.starts_with(MACROCELL_HEADER) => {}
+            _ => {
+                return Err(PatternError::Parse {
+                    line: 1,
+                    message: format!("missing '{}' header", MACROCELL_HEADER),
+                })
+            }
+        }
+        // Nodes are numbered from 1 in the order they appear, 0 is an empty square of any level
+        let mut nodes: Vec<Node> = Vec::new();
+        for (line_number, line) in
+            lines.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        {
+            let node = parse_node(line, &nodes).map_err(|message| PatternError::Parse {
+                line: line_number,
+                message,
+            })?;
+            nodes.push(node);
+        }
+        let mut cells = Vec::new();
+        if let Some(root) = nodes.last() {
+            let half = 1i64 << (root.level() - 1);
+            expand(&nodes, nodes.len(), (-half, -half), &mut cells);
+        }
+        Ok(CellPattern::new(cells))
+    }
+}
+
+/// A square of the quadtree
+enum Node {
+    /// An 8×8 square, with the live cells as offsets from its bottom left corner
+    Leaf(Vec<Position>),
+    /// A bigger square made of four half-size squares in the order northwest, northeast, southwest, southeast
+    Branch { level: u32, children: [usize; 4] },
+}
+impl Node {
+    /// The square is `2^level` cells wide
+    fn level(&self) -> u32 {
+        match self {
+            Node::Leaf(_) => LEAF_LEVEL,
+            Node::Branch { level, .. } => *level,
+        }
+    }
+}
+
+fn parse_node(line: &str, nodes: &[Node]) -> Result<Node, String> {
+    if !line.starts_with(|c: char| c.is_ascii_digit()) {
+        return parse_leaf(line).map(Node::Leaf);
+    }
+    let numbers = line
+        .split_whitespace()
+        .map(|number| {
+            number
+                .parse::<usize>()
+                .map_err(|_| format!("invalid number '{}'", number))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    let (level, children) = match numbers.as_slice() {
+        [level, nw, ne, sw, se] => (*level as u32, [*nw, *ne, *sw, *se]),
+        _ => return Err(format!("expected a level and 4 children, got '{}'", line)),
+    };
+    if level <= LEAF_LEVEL || level > MAX_LEVEL {
+        return Err(format!("unsupported node level {}", level));
+    }
+    for child in children {
+        if child > nodes.len() {
+            return Err(format!("child {} isn't defined before this node", child));
+        }
+        if child != 0 && nodes[child - 1].level() != level - 1 {
+            return Err(format!("child {} isn't of level {}", child, level - 1));
+        }
+    }
+    Ok(Node::Branch { level, children })
+}
+
+/// Parses an 8×8 leaf, with `.` for dead cells, `*` for live cells and `$` ending each row from the top
+fn parse_leaf(line: &str) -> Result<Vec<Position>, String> {
+    let mut cells = Vec::new();
+    let (mut x, mut row) = (0, 0);
+    for c in line.chars() {
+        match c {
+            '$' => {
+                x = 0;
+                row += 1;
+            }
+            '.' | '*' => {
+                if x >= LEAF_SIZE || row >= LEAF_SIZE {
+                    return Err(String::from("leaf is bigger than 8×8"));
+                }
+                if c == '*' {
+                    cells.push(Position::new(x, LEAF_SIZE - 1 - row));
+                }
+                x += 1;
+            }
+            _ => return Err(format!("unexpected character '{}' in leaf", c)),
+        }
+    }
+    Ok(cells)
+}
+
+/// Adds the cells of node number `id` with its bottom left corner at `corner`.
+///
+/// Corners are `i64` since the root square of the biggest level starts at `i32::MIN` and is `2^32` cells wide,
+/// but every cell inside it fits in a [`Position`].
+fn expand(nodes: &[Node], id: usize, corner: (i64, i64), cells: &mut Vec<Position>) {
+    if id == 0 {
+        return;
+    }
+    match &nodes[id - 1] {
+        Node::Leaf(offsets) => cells.extend(offsets.iter().map(|offset| {
+            Position::new(
+                (corner.0 + offset.x as i64) as i32,
+                (corner.1 + offset.y as i64) as i32,
+            )
+        })),
+        Node::Branch { level, children } => {
+            let half = 1i64 << (level - 1);
+            for (child, offset) in children.iter().zip(quadrant_offsets(half)) {
+                expand(
+                    nodes,
+                    *child,
+                    (corner.0 + offset.0, corner.1 + offset.1),
+                    cells,
+                );
+            }
+        }
+    }
+}
+
+/// The bottom left corners of the northwest, northeast, southwest and southeast quadrants of a square
+fn quadrant_offsets(half: i64) -> [(i64, i64); 4] {
+    [(0, half), (half, half), (0, 0), (half, 0)]
+}
+
+/// The smallest level whose square centered on the origin holds every position
+fn root_level(positions: &[Position]) -> u32 {
+    let mut level = LEAF_LEVEL;
+    let fits = |level: u32| {
+        let half = 1i64 << (level - 1);
+        positions.iter().all(|pos| {
+            (-half..half).contains(&(pos.x as i64)) && (-half..half).contains(&(pos.y as i64))
+        })
+    };
+    while !fits(level) {
+        level += 1;
+    }
+    level
+}
+
+/// Numbers and writes the distinct squares, children before their parents
+struct MacrocellWriter {
+    output: String,
+    /// The number of every line written so far
+    ids: HashMap<String, usize>,
+}
+impl MacrocellWriter {
+    /// Writes the square of the given level with its bottom left corner at `corner`, returning its node number or 0 if it's empty
+    fn write_square(&mut self, positions: Vec<Position>, corner: (i64, i64), level: u32) -> usize {
+        if positions.is_empty() {
+            return 0;
+        }
+        let line = if level == LEAF_LEVEL {
+            leaf_line(&positions, corner)
+        } else {
+            let half = 1i64 << (level - 1);
+            let mut quadrants: [Vec<Position>; 4] = Default::default();
+            for pos in positions {
+                let east = pos.x as i64 >= corner.0 + half;
+                let north = pos.y as i64 >= corner.1 + half;
+                let index = match (north, east) {
+                    (true, false) => 0,
+                    (true, true) => 1,
+                    (false, false) => 2,
+                    (false, true) => 3,
+                };
+                quadrants[index].push(pos);
+            }
+            let mut children = [0; 4];
+            for ((quadrant, offset), child) in quadrants
+                .into_iter()
+                .zip(quadrant_offsets(half))
+                .zip(&mut children)
+            {
+                *child = self.write_square(
+                    quadrant,
+                    (corner.0 + offset.0, corner.1 + offset.1),
+                    level - 1,
+                );
+            }
+            format!(
+                "{} {} {} {} {}",
+                level, children[0], children[1], children[2], children[3]
+            )
+        };
+        let next_id = self.ids.len() + 1;
+        let output = &mut self.output;
+        *self.ids.entry(line).or_insert_with_key(|line| {
+            // Writing to a string can't fail
+            let _ = writeln!(output, "{}", line);
+            next_id
+        })
+    }
+}
+
+/// Draws a leaf row by row from the top, leaving out dead cells at the end of rows and empty rows at the bottom
+fn leaf_line(positions: &[Position], corner: (i64, i64)) -> String {
+    let mut rows = vec![String::new(); LEAF_SIZE as usize];
+    let mut sorted = positions.to_vec();
+    sorted.sort_by_key(|pos| pos.x);
+    for pos in sorted {
+        let row = &mut rows[(LEAF_SIZE as i64 - 1 - (pos.y as i64 - corner.1)) as usize];
+        let x = (pos.x as i64 - corner.0) as usize;
+        while row.len() < x {
+            row.push('.');
+        }
+        row.push('*');
+    }
+    while matches!(rows.last(), Some(row) if row.is_empty()) {
+        rows.pop();
+    }
+    rows.iter().map(|row| format!("{}$", row)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_patterns::CellPattern, SimulationConfig};
+
+    fn sorted(mut positions: Vec<Position>) -> Vec<Position> {
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn round_trips_through_macrocell() {
+        let mut universe = Universe::from_pattern(&CellPattern::glider(), None);
+        universe.translate(Position::new(-20, 13));
+        universe.toggle_positions(vec![Position::new(100, -70), Position::new(-1, -1)]);
+        let text = universe.to_macrocell();
+        assert!(text.starts_with("[M2]\n"));
+        let pattern = CellPattern::from_macrocell(&text).unwrap();
+        assert_eq!(
+            sorted(pattern.cells),
+            sorted(universe.cells.keys().copied().collect())
+        );
+
+        let empty = Universe::from_positions(vec![]);
+        assert_eq!(empty.to_macrocell(), "[M2]\n");
+        assert!(CellPattern::from_macrocell(&empty.to_macrocell())
+            .unwrap()
+            .cells
+            .is_empty());
+
+        // The extremes need the biggest root square, which starts at `i32::MIN`
+        let corners = vec![
+            Position::new(i32::MIN, i32::MIN),
+            Position::new(i32::MAX, i32::MAX),
+            Position::new(i32::MIN, i32::MAX),
+        ];
+        let text = Universe::from_positions(corners.clone()).to_macrocell();
+        assert_eq!(
+            sorted(CellPattern::from_macrocell(&text).unwrap().cells),
+            sorted(corners)
+        );
+    }
+
+    #[test]
+    fn identical_squares_are_written_once() {
+        // Far apart copies of the same block share a leaf and the nodes above it
+        let blocks: Vec<Position> = (0..4)
+            .flat_map(|i| {
+                let offset = i * 64;
+                vec![
+                    Position::new(offset, 0),
+                    Position::new(offset + 1, 0),
+                    Position::new(offset, 1),
+                    Position::new(offset + 1, 1),
+                ]
+            })
+            .collect();
+        let universe = Universe::from_positions(blocks.clone());
+        let text = universe.to_macrocell();
+        assert_eq!(text.lines().filter(|line| line.contains('*')).count(), 1);
+        let mut parsed = Universe::from(&CellPattern::from_macrocell(&text).unwrap());
+        assert_eq!(
+            sorted(parsed.cells.keys().copied().collect()),
+            sorted(blocks)
+        );
+        parsed.advance(1, &SimulationConfig::default());
+        assert_eq!(parsed.population(), 16);
+    }
+
+    #[test]
+    fn reads_golly_leaves() {
+        // A glider written by Golly, with a single leaf in the southeast quadrant of a level 4 root
+        let pattern =
+            CellPattern::from_macrocell("[M2] (golly 4.0)\n#R B3/S23\n.*$.**$*.*$\n4 0 0 0 1\n")
+                .unwrap();
+        assert_eq!(pattern.normalized(), CellPattern::glider().normalized());
+        assert!(CellPattern::from_macrocell("[M2]\n4 0 0 0 1\n").is_err());
+    }
+}
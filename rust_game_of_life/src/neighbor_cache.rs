@@ -0,0 +1,140 @@
+//! Incremental neighbor counting, which skips the parts of a universe that didn't change in the previous tick.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    topology::Topology,
+    universe::{DeathCause, TickDelta, Universe},
+    utils::{Neighborhood, Position},
+    SimulationConfig,
+};
+
+/// Live neighbor counts that are kept up to date from each tick's births and deaths.
+///
+/// A cell can only change if it or one of its neighbors changed in the previous tick, so only those cells are checked.
+/// This speeds up universes that are mostly still lifes and oscillators. Only the plain rules are supported:
+/// an infinite topology with the unweighted Moore neighborhood, no walls and no births without neighbors. [`Universe::step_cached`] falls back to a full tick otherwise.
+///
+/// The cache has to be rebuilt with [`NeighborCache::new`] after editing the universe outside of [`Universe::step_cached`].
+#[derive(Clone, Debug, Default)]
+pub struct NeighborCache {
+    /// The live neighbor counts of every position that has any live neighbors
+    counts: HashMap<Position, u8>,
+    /// The positions that may change in the next tick
+    dirty: HashSet<Position>,
+}
+impl NeighborCache {
+    /// Counts the neighbors of every cell from scratch.
+    pub fn new(universe: &Universe) -> Self {
+        let mut cache = NeighborCache::default();
+        for pos in universe.cells.keys() {
+            cache.adjust_neighbors(*pos, true);
+        }
+        cache
+    }
+    /// The number of live neighbors of `pos`
+    pub fn get(&self, pos: Position) -> u8 {
+        self.counts.get(&pos).copied().unwrap_or(0)
+    }
+    /// Computes the next tick of `universe` by only checking the positions around the previous changes.
+    ///
+    /// The result is the same as [`Universe::tick_delta`] for the supported configs.
+    pub fn next_delta(&self, universe: &Universe, config: &SimulationConfig) -> TickDelta {
+        let mut delta = TickDelta::default();
+        for pos in &self.dirty {
+            let live_neighbors = self.get(*pos);
+            if universe.cells.contains_key(pos) {
+                if !config.survives(live_neighbors) && !universe.is_frozen(*pos) {
+                    let cause =
+                        DeathCause::from_neighbor_count(live_neighbors, &config.allowed_neighbors);
+                    delta.deaths.push((*pos, cause));
+                }
+            } else if live_neighbors > 0 && config.is_born(live_neighbors) {
+                delta.births.push(*pos);
+            }
+        }
+        delta.births.sort();
+        delta.deaths.sort_by_key(|(pos, _)| *pos);
+        delta
+    }
+    /// Updates the counts around the births and deaths of `delta` and marks them for the next tick.
+    pub fn apply(&mut self, delta: &TickDelta) {
+        self.dirty.clear();
+        for pos in &delta.births {
+            self.adjust_neighbors(*pos, true);
+        }
+        for (pos, _) in &delta.deaths {
+            self.adjust_neighbors(*pos, false);
+        }
+    }
+    /// Whether the cache gives the same results as a full tick under `config`
+    pub fn supports(config: &SimulationConfig, universe: &Universe) -> bool {
+        config.topology == Topology::Infinite
+            && config.neighborhood == Neighborhood::Moore
+            && config.neighbor_weights.is_none()
+            && universe.walls().is_empty()
+            && !config.allowed_neighbors_for_birth.contains(&0)
+            && universe.background().is_none()
+    }
+    /// Adds or removes a live cell at `pos` from the counts of its neighbors
+    fn adjust_neighbors(&mut self, pos: Position, born: bool) {
+        self.dirty.insert(pos);
+        for neighbor_pos in pos.neighbors() {
+            self.dirty.insert(neighbor_pos);
+            let count = self.counts.entry(neighbor_pos).or_insert(0);
+            if born {
+                *count += 1;
+            } else {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&neighbor_pos);
+                }
+            }
+        }
+    }
+}
+
+impl Universe {
+    /// Plays one frame like [`Universe::step`], using and updating `cache` to avoid recounting unchanged areas.
+    ///
+    /// Falls back to a full tick and rebuilds the cache if `config` isn't supported, see [`NeighborCache`].
+    pub fn step_cached(
+        &mut self,
+        config: &SimulationConfig,
+        cache: &mut NeighborCache,
+    ) -> TickDelta {
+        if !NeighborCache::supports(config, self) {
+            let delta = self.step(config);
+            *cache = NeighborCache::new(self);
+            return delta;
+        }
+        let delta = cache.next_delta(self, config);
+        cache.apply(&delta);
+        self.step_with_delta(delta, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::utils::SizeInt;
+
+    #[test]
+    fn matches_a_full_recount() {
+        let config = SimulationConfig::default();
+        for seed in 0..5 {
+            let mut naive =
+                Universe::random(SizeInt::new(30, 30), 0.35, &mut StdRng::seed_from_u64(seed));
+            let mut cached = naive.fork();
+            let mut cache = NeighborCache::new(&cached);
+            for _ in 0..150 {
+                assert_eq!(cached.step_cached(&config, &mut cache), naive.step(&config));
+            }
+            for pos in cached.cells.keys().flat_map(|pos| pos.neighbors()) {
+                assert_eq!(cache.get(pos), naive.live_neighbor_count(pos));
+            }
+        }
+    }
+}
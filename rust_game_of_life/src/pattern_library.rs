@@ -0,0 +1,89 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    catalog::{self, PatternInfo},
+    cell_patterns::{is_supported_file, CellPattern, PatternError},
+};
+
+/// A collection of patterns loaded from a directory, keyed by file name without the extension
+#[derive(Debug, Default)]
+pub struct PatternLibrary {
+    patterns: BTreeMap<String, CellPattern>,
+    errors: Vec<(PathBuf, PatternError)>,
+}
+impl PatternLibrary {
+    /// Loads every supported pattern file in `dir`.
+    ///
+    /// Only failing to read the directory itself is an error. Files that fail to load are skipped
+    /// and can be inspected with [`PatternLibrary::errors`].
+    pub fn from_dir(dir: impl AsRef<Path>) -> io::Result<PatternLibrary> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_supported_file(&path) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut library = PatternLibrary::default();
+        for path in paths {
+            let name = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            match CellPattern::from_file(&path) {
+                Ok(pattern) => {
+                    library.patterns.insert(name, pattern);
+                }
+                Err(err) => library.errors.push((path, err)),
+            }
+        }
+        Ok(library)
+    }
+    pub fn get(&self, name: &str) -> Option<&CellPattern> {
+        self.patterns.get(name)
+    }
+    /// The catalog metadata of a loaded pattern, if its name matches a pattern in the [catalog](crate::catalog)
+    pub fn info(&self, name: &str) -> Option<&'static PatternInfo> {
+        self.patterns.get(name).and(catalog::info(name))
+    }
+    /// The names of all loaded patterns in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        self.patterns.keys().map(String::as_str).collect()
+    }
+    /// The files that couldn't be loaded and why
+    pub fn errors(&self) -> &[(PathBuf, PatternError)] {
+        &self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_errors_without_aborting() {
+        let dir = std::env::temp_dir().join("rust_game_of_life_pattern_library");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("glider.rle"), "x = 3, y = 3\nbob$2bo$3o!").unwrap();
+        fs::write(dir.join("blinker.RLE"), "x = 3, y = 1\n3o!").unwrap();
+        fs::write(dir.join("broken.rle"), "x = 3, y = 3\nbo?!").unwrap();
+        fs::write(dir.join("notes.txt"), "not a pattern").unwrap();
+
+        let library = PatternLibrary::from_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(library.names(), vec!["blinker", "glider"]);
+        assert_eq!(library.get("glider").unwrap().cells.len(), 5);
+        assert_eq!(library.info("glider").unwrap().period, Some(4));
+        assert!(library.info("broken").is_none());
+        assert_eq!(library.errors().len(), 1);
+        assert!(library.errors()[0].0.ends_with("broken.rle"));
+    }
+}
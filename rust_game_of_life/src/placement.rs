@@ -0,0 +1,204 @@
+//! Stamping patterns into a universe without ruining what's already there.
+
+use std::{error::Error, fmt};
+
+use bevy::prelude::*;
+
+use crate::{
+    cell_patterns::{CellPattern, PatternError},
+    universe::{CellState, Universe},
+    utils::Position,
+};
+
+/// How far [`PlacementMode::NearestFree`] looks for a free spot, in cells from the requested offset
+pub const MAX_PLACEMENT_SEARCH: i32 = 64;
+
+/// What [`Universe::insert_pattern`] does when a pattern would collide with live cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PlacementMode {
+    /// Place the pattern anyway, merging it with the cells it overlaps
+    #[default]
+    Overlap,
+    /// Leave the universe unchanged
+    Skip,
+    /// Leave the universe unchanged and return a [`PlacementError`]
+    Error,
+    /// Move the pattern to the closest offset where it fits, searching up to [`MAX_PLACEMENT_SEARCH`] cells away
+    NearestFree,
+}
+
+/// A pattern couldn't be placed without colliding with live cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlacementError {
+    /// The offset the pattern was supposed to be placed at
+    pub offset: Position,
+}
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "pattern collides with live cells at offset ({}, {})",
+            self.offset.x, self.offset.y
+        )
+    }
+}
+impl Error for PlacementError {}
+
+impl Universe {
+    /// Whether `pattern` moved by `offset` would be at least `margin` cells away from every live cell.
+    ///
+    /// A `margin` of 0 only checks the pattern's own cells, 1 also keeps it from touching live cells, and so on.
+    pub fn can_place(&self, pattern: &CellPattern, offset: Position, margin: i32) -> bool {
+        let margin = margin.max(0);
+        pattern.cells.iter().all(|pos| {
+            let pos = Position::new(pos.x + offset.x, pos.y + offset.y);
+            (pos.y - margin..=pos.y + margin).all(|y| {
+                (pos.x - margin..=pos.x + margin)
+                    .all(|x| self.state_at(Position::new(x, y)) == CellState::Dead)
+            })
+        })
+    }
+    /// Sets the cells of `pattern` moved by `offset` alive, handling collisions according to `mode`.
+    ///
+    /// Returns the offset the pattern was placed at, or `None` if it was skipped.
+    /// Collisions are checked with [`Universe::can_place`] using `margin`.
+    pub fn insert_pattern(
+        &mut self,
+        commands: &mut Commands,
+        pattern: &CellPattern,
+        offset: Position,
+        margin: i32,
+        mode: PlacementMode,
+    ) -> Result<Option<Position>, PlacementError> {
+        let offset = match mode {
+            PlacementMode::Overlap => Some(offset),
+            _ if self.can_place(pattern, offset, margin) => Some(offset),
+            PlacementMode::Skip => None,
+            PlacementMode::Error => return Err(PlacementError { offset }),
+            PlacementMode::NearestFree => self.nearest_free_offset(pattern, offset, margin),
+        };
+        if let Some(offset) = offset {
+            let positions = pattern
+                .cells
+                .iter()
+                .map(|pos| Position::new(pos.x + offset.x, pos.y + offset.y))
+                .filter(|pos| self.state_at(*pos) == CellState::Dead)
+                .collect();
+            self.toggle_cells_at(commands, positions);
+        }
+        Ok(offset)
+    }
+    /// Parses [RLE](crate::rle) text and sets its cells alive at `offset`, merging it with the cells it overlaps.
+    ///
+    /// Meant for pasting patterns from the clipboard. The universe is left unchanged if the text doesn't parse.
+    pub fn stamp_rle(
+        &mut self,
+        commands: &mut Commands,
+        rle: &str,
+        offset: Position,
+    ) -> Result<(), PatternError> {
+        let pattern = CellPattern::from_rle(rle)?;
+        // Overlapping placements never fail
+        let _ = self.insert_pattern(commands, &pattern, offset, 0, PlacementMode::Overlap);
+        Ok(())
+    }
+    /// Searches rings of growing [Chebyshev distance](https://en.wikipedia.org/wiki/Chebyshev_distance) around `offset`,
+    /// preferring the closest offset by straight-line distance within each ring.
+    fn nearest_free_offset(
+        &self,
+        pattern: &CellPattern,
+        offset: Position,
+        margin: i32,
+    ) -> Option<Position> {
+        (1..=MAX_PLACEMENT_SEARCH).find_map(|radius| {
+            let mut ring: Vec<(i32, i32)> = (-radius..=radius)
+                .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+                .filter(|(dx, dy)| dx.abs().max(dy.abs()) == radius)
+                .collect();
+            ring.sort_by_key(|(dx, dy)| (dx * dx + dy * dy, *dy, *dx));
+            ring.into_iter()
+                .map(|(dx, dy)| Position::new(offset.x + dx, offset.y + dy))
+                .find(|candidate| self.can_place(pattern, *candidate, margin))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use super::*;
+
+    #[test]
+    fn pastes_rle_text() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut universe = Universe::default();
+        let offset = Position::new(10, -4);
+        universe
+            .stamp_rle(&mut commands, "x = 3, y = 3\nbob$2bo$3o!", offset)
+            .unwrap();
+        assert_eq!(universe.population(), 5);
+        assert!(universe.cells.contains_key(&offset));
+
+        assert!(universe
+            .stamp_rle(&mut commands, "bob$2bo$3o!", Position::new(0, 0))
+            .is_err());
+        assert_eq!(universe.population(), 5);
+    }
+
+    #[test]
+    fn detects_overlapping_gliders() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let glider = CellPattern::glider();
+        let mut universe = Universe::default();
+        let origin = Position::new(0, 0);
+        assert_eq!(
+            universe.insert_pattern(&mut commands, &glider, origin, 0, PlacementMode::Error),
+            Ok(Some(origin))
+        );
+        assert!(!universe.can_place(&glider, origin, 0));
+        assert_eq!(
+            universe.insert_pattern(&mut commands, &glider, origin, 0, PlacementMode::Error),
+            Err(PlacementError { offset: origin })
+        );
+        assert_eq!(
+            universe.insert_pattern(&mut commands, &glider, origin, 0, PlacementMode::Skip),
+            Ok(None)
+        );
+        assert_eq!(universe.population(), 5);
+
+        // The glider's bounding box is 3 cells wide, so it fits right next to the first one without a margin
+        assert!(universe.can_place(&glider, Position::new(3, 0), 0));
+        assert!(!universe.can_place(&glider, Position::new(3, 0), 1));
+    }
+
+    #[test]
+    fn moves_patterns_to_the_nearest_free_offset() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let glider = CellPattern::glider();
+        let mut universe = Universe::from_pattern(&glider, None);
+        let placed = universe
+            .insert_pattern(
+                &mut commands,
+                &glider,
+                Position::new(0, 0),
+                1,
+                PlacementMode::NearestFree,
+            )
+            .unwrap()
+            .unwrap();
+        assert_ne!(placed, Position::new(0, 0));
+        assert_eq!(universe.population(), 10);
+        // The first glider is still there untouched
+        assert!(glider
+            .cells
+            .iter()
+            .all(|pos| universe.cells.contains_key(pos)));
+    }
+}
@@ -0,0 +1,96 @@
+//! Capturing frames of a running universe for animations, without a game engine.
+//!
+//! The frames are plain state buffers, see [`Universe::write_state_buffer`], so any image or video encoder can turn them into a GIF or video.
+
+use std::time::Duration;
+
+use crate::{
+    universe::{Bounds, Universe},
+    utils::SizeInt,
+    SimulationConfig,
+};
+
+/// A captured frame
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The [`Universe::generation`] the frame shows
+    pub generation: u64,
+    /// The width and height of the frame in cells
+    pub size: SizeInt,
+    /// 1 for live and 0 for dead cells, in row-major order from the bottom left
+    pub cells: Vec<u8>,
+}
+
+/// Settings for capturing frames of a simulation, independent of how fast it would tick on screen
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameRecorder {
+    /// How many generations pass between captured frames, higher values give a fast-motion effect
+    pub generations_per_frame: u32,
+    /// The frame rate of the animation the frames are meant for
+    pub fps: u32,
+    /// The area captured in every frame, the universe's bounds at each frame if `None`
+    pub viewport: Option<Bounds>,
+}
+impl FrameRecorder {
+    /// How long each frame should be shown for at [`FrameRecorder::fps`]
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps.max(1) as f64)
+    }
+    /// Simulates `generations` generations without spawning entities, capturing a frame after every [`FrameRecorder::generations_per_frame`].
+    ///
+    /// The starting state isn't captured, and generations left over at the end don't produce a frame.
+    pub fn record(
+        &self,
+        universe: &mut Universe,
+        generations: u32,
+        config: &SimulationConfig,
+    ) -> Vec<Frame> {
+        let generations_per_frame = self.generations_per_frame.max(1);
+        let mut frames = Vec::with_capacity((generations / generations_per_frame) as usize);
+        for _ in 0..generations / generations_per_frame {
+            universe.advance(generations_per_frame, config);
+            let mut cells = vec![];
+            let bounds = self.viewport.unwrap_or_else(|| universe.bounds());
+            let size = universe.write_state_buffer(bounds, &mut cells);
+            frames.push(Frame {
+                generation: universe.generation(),
+                size,
+                cells,
+            });
+        }
+        universe.advance(generations % generations_per_frame, config);
+        frames
+    }
+}
+impl Default for FrameRecorder {
+    fn default() -> Self {
+        Self {
+            generations_per_frame: 1,
+            fps: 30,
+            viewport: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell_patterns::CellPattern;
+
+    #[test]
+    fn skips_generations_between_frames() {
+        let recorder = FrameRecorder {
+            generations_per_frame: 10,
+            ..Default::default()
+        };
+        let mut universe = Universe::from(&CellPattern::glider());
+        let frames = recorder.record(&mut universe, 100, &SimulationConfig::default());
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[0].generation, 10);
+        assert_eq!(frames[9].generation, 100);
+        assert!(frames
+            .iter()
+            .all(|frame| frame.cells.iter().filter(|cell| **cell == 1).count() == 5));
+        assert_eq!(universe.generation(), 100);
+    }
+}
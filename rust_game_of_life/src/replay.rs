@@ -0,0 +1,278 @@
+//! Recording a run as its seed and manual edits, which is enough to reproduce it exactly because the simulation is deterministic.
+//!
+//! Replays are saved as small text files:
+//!
+//! ```text
+//! #replay 1
+//! seed 42
+//! size 32 32
+//! life_chance 0.4
+//! warmup 0
+//! rule B3/S23
+//! end 120
+//! toggle 15 3,4 -2,7
+//! ```
+//!
+//! Each `toggle` line lists the cells toggled at the given [`Universe::generation`].
+
+use std::{fmt::Write as _, fs, path::Path};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cell_patterns::PatternError,
+    rule::Rule,
+    universe::Universe,
+    utils::{Position, SizeInt},
+    SimulationConfig,
+};
+
+/// The first line of every replay file
+const REPLAY_HEADER: &str = "#replay 1";
+
+/// Cells that were toggled by hand during a recorded run
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayEdit {
+    /// The [`Universe::generation`] the cells were toggled at, before that generation was ticked
+    pub generation: u64,
+    pub positions: Vec<Position>,
+}
+
+/// A recorded run, see the [module docs](self) for the file format.
+///
+/// Only the rule and the generation settings are recorded. The topology, neighborhood and other settings
+/// come from the config passed to [`Replay::play`], so it should match the one the run was recorded with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub initial_size: SizeInt,
+    pub life_chance: f32,
+    pub warmup_generations: u32,
+    pub rule: Rule,
+    /// Edits in the order they happened
+    pub edits: Vec<ReplayEdit>,
+    /// The generation the recording stopped at
+    pub end_generation: u64,
+}
+impl Replay {
+    /// Starts recording a run of `config` generated from `seed`.
+    ///
+    /// The run itself should start from [`Replay::start`] to match the replay.
+    pub fn new(config: &SimulationConfig, seed: u64) -> Self {
+        let generation = &config.generation;
+        Self {
+            seed,
+            initial_size: generation.initial_size,
+            life_chance: generation.life_chance,
+            warmup_generations: generation.warmup_generations,
+            rule: config.rule(),
+            edits: vec![],
+            end_generation: generation.warmup_generations as u64,
+        }
+    }
+    /// Generates the universe the recorded run started from, including the warmup, without spawning any entities.
+    pub fn start(&self, config: &SimulationConfig) -> Universe {
+        let config = self.config(config);
+        let mut universe = Universe::random(
+            self.initial_size,
+            self.life_chance,
+            &mut StdRng::seed_from_u64(self.seed),
+        );
+        universe.advance(self.warmup_generations, &config);
+        universe
+    }
+    /// Records that `positions` were toggled at `generation`.
+    pub fn record_toggle(&mut self, generation: u64, positions: Vec<Position>) {
+        self.end_generation = self.end_generation.max(generation);
+        self.edits.push(ReplayEdit {
+            generation,
+            positions,
+        });
+    }
+    /// Records that the run got to `generation`.
+    pub fn record_generation(&mut self, generation: u64) {
+        self.end_generation = self.end_generation.max(generation);
+    }
+    /// Replays the run from the start to [`Replay::end_generation`], returning the final universe without any entities.
+    ///
+    /// Uses the recorded rule with the rest of the settings from `config`.
+    pub fn play(&self, config: &SimulationConfig) -> Universe {
+        let config = self.config(config);
+        let mut universe = self.start(&config);
+        let mut edits = self.edits.iter().peekable();
+        loop {
+            while let Some(edit) = edits.next_if(|edit| edit.generation <= universe.generation()) {
+                universe.toggle_positions(edit.positions.iter().copied());
+            }
+            if universe.generation() >= self.end_generation {
+                return universe;
+            }
+            universe.step(&config);
+        }
+    }
+    /// `config` with the recorded rule and generation settings
+    fn config(&self, config: &SimulationConfig) -> SimulationConfig {
+        let mut config = config.clone();
+        config.set_rule(self.rule.clone());
+        config.generation.seed = Some(self.seed);
+        config.generation.initial_size = self.initial_size;
+        config.generation.life_chance = self.life_chance;
+        config.generation.warmup_generations = self.warmup_generations;
+        config
+    }
+    /// Writes the replay in the text format described in the [module docs](self).
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(REPLAY_HEADER);
+        text.push('\n');
+        // Writing to a string can't fail
+        let _ = writeln!(text, "seed {}", self.seed);
+        let _ = writeln!(
+            text,
+            "size {} {}",
+            self.initial_size.width, self.initial_size.height
+        );
+        let _ = writeln!(text, "life_chance {}", self.life_chance);
+        let _ = writeln!(text, "warmup {}", self.warmup_generations);
+        let _ = writeln!(text, "rule {}", self.rule);
+        let _ = writeln!(text, "end {}", self.end_generation);
+        for edit in &self.edits {
+            let _ = write!(text, "toggle {}", edit.generation);
+            for pos in &edit.positions {
+                let _ = write!(text, " {},{}", pos.x, pos.y);
+            }
+            text.push('\n');
+        }
+        text
+    }
+    /// Reads a replay written by [`Replay::to_text`].
+    pub fn from_text(input: &str) -> Result<Replay, PatternError> {
+        let mut lines = input.lines().enumerate();
+        match lines.next() {
+            Some((_, line)) if line.trim() == REPLAY_HEADER => {}
+            _ => {
+                return Err(PatternError::Parse {
+                    line: 1,
+                    message: format!("expected '{}'", REPLAY_HEADER),
+                })
+            }
+        }
+        let mut replay = Replay::new(&SimulationConfig::default(), 0);
+        for (index, line) in lines {
+            let error = |message: String| PatternError::Parse {
+                line: index + 1,
+                message,
+            };
+            let mut words = line.split_whitespace();
+            let key = match words.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let mut next = |what: &str| {
+                words
+                    .next()
+                    .ok_or_else(|| error(format!("missing {} after '{}'", what, key)))
+            };
+            let number_error = |word: &str| error(format!("invalid number '{}'", word));
+            match key {
+                "seed" => {
+                    let word = next("seed")?;
+                    replay.seed = word.parse().map_err(|_| number_error(word))?;
+                }
+                "size" => {
+                    let width = next("width")?;
+                    let height = next("height")?;
+                    replay.initial_size = SizeInt::new(
+                        width.parse().map_err(|_| number_error(width))?,
+                        height.parse().map_err(|_| number_error(height))?,
+                    );
+                }
+                "life_chance" => {
+                    let word = next("chance")?;
+                    replay.life_chance = word.parse().map_err(|_| number_error(word))?;
+                }
+                "warmup" => {
+                    let word = next("generations")?;
+                    replay.warmup_generations = word.parse().map_err(|_| number_error(word))?;
+                }
+                "rule" => {
+                    let word = next("rule")?;
+                    replay.rule = Rule::parse(word)
+                        .ok_or_else(|| error(format!("invalid rule '{}'", word)))?;
+                }
+                "end" => {
+                    let word = next("generation")?;
+                    replay.end_generation = word.parse().map_err(|_| number_error(word))?;
+                }
+                "toggle" => {
+                    let word = next("generation")?;
+                    let generation = word.parse().map_err(|_| number_error(word))?;
+                    let positions = words
+                        .map(|word| {
+                            let (x, y) = word
+                                .split_once(',')
+                                .ok_or_else(|| error(format!("expected 'x,y', got '{}'", word)))?;
+                            Ok(Position::new(
+                                x.parse().map_err(|_| number_error(word))?,
+                                y.parse().map_err(|_| number_error(word))?,
+                            ))
+                        })
+                        .collect::<Result<_, PatternError>>()?;
+                    replay.edits.push(ReplayEdit {
+                        generation,
+                        positions,
+                    });
+                }
+                _ => return Err(error(format!("unknown entry '{}'", key))),
+            }
+        }
+        Ok(replay)
+    }
+    /// Saves the replay to a file, see [`Replay::to_text`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PatternError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+    /// Loads a replay saved with [`Replay::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, PatternError> {
+        Replay::from_text(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaying_reproduces_the_run() {
+        let mut config = SimulationConfig::default();
+        config.set_rule(Rule::parse("B36/S23").unwrap());
+        config.generation.warmup_generations = 3;
+        let mut replay = Replay::new(&config, 7);
+
+        // The original run, with edits in between ticks
+        let mut universe = replay.start(&config);
+        for _ in 0..40 {
+            if [3, 13, 24, 42].contains(&universe.generation()) {
+                let positions = vec![Position::new(0, 0), Position::new(20, -20)];
+                replay.record_toggle(universe.generation(), positions.clone());
+                universe.toggle_positions(positions);
+            }
+            universe.step(&config);
+            replay.record_generation(universe.generation());
+        }
+
+        let loaded = Replay::from_text(&replay.to_text()).unwrap();
+        assert_eq!(loaded, replay);
+        assert!(loaded
+            .play(&SimulationConfig::default())
+            .diff(&universe)
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_entries() {
+        let error = Replay::from_text("#replay 1\nseed 1\nspeed 3\n").unwrap_err();
+        assert!(matches!(error, PatternError::Parse { line: 3, .. }));
+        assert!(Replay::from_text("seed 1\n").is_err());
+    }
+}
@@ -0,0 +1,408 @@
+//! Parsing for the [RLE](https://conwaylife.com/wiki/Run_Length_Encoded) pattern format.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use bevy::log::warn;
+
+use crate::{
+    cell_patterns::{CellPattern, PatternError},
+    rule::Rule,
+    universe::Universe,
+    utils::Position,
+    SimulationConfig,
+};
+
+/// The longest line [`write_rle`] writes, as recommended by the format
+const MAX_LINE_LENGTH: usize = 70;
+
+/// A pattern together with the simulation settings it was designed for
+pub struct LoadedPattern {
+    pub pattern: CellPattern,
+    /// The default config with the rule from the pattern's header
+    pub config: SimulationConfig,
+    /// The name, author and comments from the `#` lines
+    pub metadata: RleMetadata,
+}
+
+/// The parsed `x = m, y = n, rule = abc` header line of an RLE file, along with the `#` lines around it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RleHeader {
+    pub width: i32,
+    pub height: i32,
+    pub rule: Option<String>,
+    pub metadata: RleMetadata,
+}
+
+/// The `#` lines of an RLE file, for showing the name and author of a pattern in a pattern picker
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RleMetadata {
+    /// The `#N` line
+    pub name: Option<String>,
+    /// The `#O` line, who made or found the pattern
+    pub author: Option<String>,
+    /// The `#C` and `#c` lines in order, without the tag
+    pub comments: Vec<String>,
+    /// The `#r` line, a rule in the older `survival/birth` notation
+    pub rule: Option<String>,
+    /// The top left corner from a `#P` or `#R` line, in the y-down coordinates of the file
+    pub top_left: Option<Position>,
+    /// Lines with an unknown tag or an unreadable value, kept as they are including the `#`
+    pub raw_comments: Vec<String>,
+}
+impl RleMetadata {
+    /// Sorts a `#` line into its field, later lines replace earlier ones for single values.
+    fn add_line(&mut self, line: &str) {
+        let mut chars = line.chars();
+        chars.next();
+        let tag = chars.next();
+        let text = chars.as_str().trim();
+        match tag {
+            Some('N') => self.name = Some(text.to_string()),
+            Some('O') => self.author = Some(text.to_string()),
+            Some('C' | 'c') => self.comments.push(text.to_string()),
+            Some('r') => self.rule = Some(text.to_string()),
+            Some('P' | 'R') => match parse_corner(text) {
+                Some(corner) => self.top_left = Some(corner),
+                None => self.raw_comments.push(line.to_string()),
+            },
+            _ => self.raw_comments.push(line.to_string()),
+        }
+    }
+}
+
+fn parse_corner(text: &str) -> Option<Position> {
+    let mut coordinates = text.split_whitespace().map(|value| value.parse().ok());
+    match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Some(x)), Some(Some(y)), None) => Some(Position::new(x, y)),
+        _ => None,
+    }
+}
+impl RleHeader {
+    fn parse(line: &str, line_number: usize) -> Result<RleHeader, PatternError> {
+        let error = |message: String| PatternError::Parse {
+            line: line_number,
+            message,
+        };
+        let mut header = RleHeader::default();
+        let mut has_width = false;
+        let mut has_height = false;
+        for field in line.split(',') {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected 'key = value', got '{}'", field.trim())))?;
+            let value = value.trim();
+            match key.trim() {
+                "x" => {
+                    header.width = parse_dimension(value).map_err(error)?;
+                    has_width = true;
+                }
+                "y" => {
+                    header.height = parse_dimension(value).map_err(error)?;
+                    has_height = true;
+                }
+                "rule" => header.rule = Some(value.to_string()),
+                // Unknown keys are tolerated for forward compatibility
+                _ => {}
+            }
+        }
+        if !has_width || !has_height {
+            return Err(error(String::from("header is missing 'x' or 'y'")));
+        }
+        Ok(header)
+    }
+}
+
+fn parse_dimension(value: &str) -> Result<i32, String> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|dimension| *dimension >= 0)
+        .ok_or_else(|| format!("invalid dimension '{}'", value))
+}
+
+impl CellPattern {
+    /// Parses a pattern from RLE text.
+    ///
+    /// RLE rows go from top to bottom, so they are flipped to match the crate's y-up coordinates,
+    /// with the bottom left corner of the header's bounding box at the origin.
+    pub fn from_rle(input: &str) -> Result<CellPattern, PatternError> {
+        parse_rle(input).map(|(_, pattern)| pattern)
+    }
+    /// Parses a pattern from RLE text like [`CellPattern::from_rle`], along with the metadata in its `#` lines.
+    pub fn from_rle_with_metadata(input: &str) -> Result<(CellPattern, RleMetadata), PatternError> {
+        parse_rle(input).map(|(header, pattern)| (pattern, header.metadata))
+    }
+    /// Parses RLE text, calling `callback` with every live cell instead of collecting them, and returns the header.
+    ///
+    /// Keeps memory use flat for huge patterns, whose cells can be inserted straight into a universe.
+    /// Positions are the same as the ones [`CellPattern::from_rle`] returns, in the same order.
+    pub fn parse_rle_into<F: FnMut(Position)>(
+        input: &str,
+        callback: F,
+    ) -> Result<RleHeader, PatternError> {
+        parse_rle_cells(input, callback)
+    }
+    /// Parses a pattern from RLE text along with the rule given in its header.
+    ///
+    /// Falls back to Conway's rule with a warning if the header has no rule or the rule isn't recognized.
+    pub fn from_rle_with_config(input: &str) -> Result<LoadedPattern, PatternError> {
+        let (header, pattern) = parse_rle(input)?;
+        let rule = match header.rule {
+            Some(rule) => Rule::parse(&rule).unwrap_or_else(|| {
+                warn!(
+                    "Unrecognized rule '{}', falling back to Conway's rule",
+                    rule
+                );
+                Rule::conway()
+            }),
+            None => {
+                warn!("Pattern has no rule, falling back to Conway's rule");
+                Rule::conway()
+            }
+        };
+        let mut config = SimulationConfig::default();
+        config.set_rule(rule);
+        Ok(LoadedPattern {
+            pattern,
+            config,
+            metadata: header.metadata,
+        })
+    }
+    /// Loads an RLE file along with the rule given in its header, see [`CellPattern::from_rle_with_config`].
+    pub fn from_file_with_config(path: impl AsRef<Path>) -> Result<LoadedPattern, PatternError> {
+        let contents = fs::read_to_string(path)?;
+        CellPattern::from_rle_with_config(&contents)
+    }
+}
+
+fn parse_rle(input: &str) -> Result<(RleHeader, CellPattern), PatternError> {
+    let mut cells = vec![];
+    let header = parse_rle_cells(input, |pos| cells.push(pos))?;
+    Ok((header, CellPattern::new(cells)))
+}
+
+fn parse_rle_cells(input: &str, callback: impl FnMut(Position)) -> Result<RleHeader, PatternError> {
+    parse_rle_lines(input.lines().map(Ok), callback)
+}
+
+/// Parses RLE one line at a time, so that a reader never has to be loaded into memory as a whole
+pub(crate) fn parse_rle_lines<S: AsRef<str>>(
+    lines: impl Iterator<Item = io::Result<S>>,
+    mut callback: impl FnMut(Position),
+) -> Result<RleHeader, PatternError> {
+    let mut lines = lines.enumerate();
+    let mut metadata = RleMetadata::default();
+    let comments = &mut metadata;
+    // Skips blank lines and collects comments, returning the 1-based line number with the line
+    let mut next_line = move || -> Result<Option<(usize, S)>, PatternError> {
+        for (index, line) in lines.by_ref() {
+            let line = line?;
+            let trimmed = line.as_ref().trim();
+            if trimmed.starts_with('#') {
+                comments.add_line(trimmed);
+            } else if !trimmed.is_empty() {
+                return Ok(Some((index + 1, line)));
+            }
+        }
+        Ok(None)
+    };
+
+    let (header_line, header) = next_line()?.ok_or(PatternError::Parse {
+        line: 1,
+        message: String::from("missing RLE header"),
+    })?;
+    let mut header = RleHeader::parse(header.as_ref().trim(), header_line)?;
+
+    let mut row = 0;
+    let mut column = 0;
+    // Run counts may be split across lines, so the count is carried over
+    let mut run_count: Option<i32> = None;
+    'lines: while let Some((line_number, line)) = next_line()? {
+        for ch in line.as_ref().trim().chars() {
+            if let Some(digit) = ch.to_digit(10) {
+                let count = run_count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(digit as i32))
+                    .ok_or(PatternError::Parse {
+                        line: line_number,
+                        message: String::from("run count is too large"),
+                    })?;
+                run_count = Some(count);
+                continue;
+            }
+            if ch.is_whitespace() {
+                continue;
+            }
+            let run = run_count.take().unwrap_or(1);
+            match ch {
+                'b' | '.' => column += run,
+                'o' | 'A'..='X' => {
+                    for _ in 0..run {
+                        callback(Position::new(column, header.height - 1 - row));
+                        column += 1;
+                    }
+                }
+                '$' => {
+                    row += run;
+                    column = 0;
+                }
+                '!' => break 'lines,
+                ch => {
+                    return Err(PatternError::Parse {
+                        line: line_number,
+                        message: format!("unexpected character '{}'", ch),
+                    })
+                }
+            }
+        }
+    }
+    header.metadata = metadata;
+    Ok(header)
+}
+
+/// Writes the live cells of `universe` as RLE without a rule, with the bottom left corner of the bounds at the origin when read back.
+///
+/// Rows without live cells are merged into a single run of `$`, and dead cells at the end of a row are left out.
+pub(crate) fn write_rle(universe: &Universe, mut writer: impl Write) -> io::Result<()> {
+    let bounds = universe.bounds();
+    if bounds.is_empty() {
+        return writeln!(writer, "x = 0, y = 0\n!");
+    }
+    writeln!(
+        writer,
+        "x = {}, y = {}",
+        bounds.right - bounds.left + 1,
+        bounds.top - bounds.bottom + 1
+    )?;
+    let mut rows: HashMap<i32, Vec<i32>> = HashMap::new();
+    for pos in universe.live_positions() {
+        rows.entry(pos.y).or_default().push(pos.x);
+    }
+    let mut row_keys: Vec<i32> = rows.keys().copied().collect();
+    // RLE rows go from top to bottom
+    row_keys.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut line = String::new();
+    let mut push = |count: i32, tag: char, writer: &mut dyn Write| -> io::Result<()> {
+        let token = match count {
+            1 => tag.to_string(),
+            count => format!("{}{}", count, tag),
+        };
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            writeln!(writer, "{}", line)?;
+            line.clear();
+        }
+        line.push_str(&token);
+        Ok(())
+    };
+    let mut previous_row = bounds.top;
+    for y in row_keys {
+        if y < previous_row {
+            push(previous_row - y, '$', &mut writer)?;
+        }
+        previous_row = y;
+        let row = rows.get_mut(&y).expect("row keys come from the rows");
+        row.sort_unstable();
+        let mut column = bounds.left;
+        let mut xs = row.iter().peekable();
+        while let Some(&start) = xs.next() {
+            let mut end = start;
+            while xs.peek() == Some(&&(end + 1)) {
+                end += 1;
+                xs.next();
+            }
+            if start > column {
+                push(start - column, 'b', &mut writer)?;
+            }
+            push(end - start + 1, 'o', &mut writer)?;
+            column = end + 1;
+        }
+    }
+    push(1, '!', &mut writer)?;
+    writeln!(writer, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_glider() {
+        let pattern =
+            CellPattern::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+        assert_eq!(
+            pattern.cells,
+            vec![
+                Position::new(1, 2),
+                Position::new(2, 1),
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_comment_metadata() {
+        let rle = "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\n#C Found in 1969.\n#P -1 -1\n#Z 42\nx = 3, y = 3\nbob$2bo$3o!";
+        let (pattern, metadata) = CellPattern::from_rle_with_metadata(rle).unwrap();
+        assert_eq!(pattern.cells.len(), 5);
+        assert_eq!(metadata.name.as_deref(), Some("Glider"));
+        assert_eq!(metadata.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(
+            metadata.comments,
+            vec!["The smallest spaceship.", "Found in 1969."]
+        );
+        assert_eq!(metadata.top_left, Some(Position::new(-1, -1)));
+        assert_eq!(metadata.raw_comments, vec!["#Z 42"]);
+
+        let (_, empty) = CellPattern::from_rle_with_metadata("x = 1, y = 1\no!").unwrap();
+        assert_eq!(empty, RleMetadata::default());
+    }
+
+    #[test]
+    fn streams_cells_to_a_callback() {
+        let mut count = 0;
+        let header =
+            CellPattern::parse_rle_into("x = 3, y = 3\nbob$2bo$3o!", |_| count += 1).unwrap();
+        assert_eq!(count, 5);
+        assert_eq!((header.width, header.height), (3, 3));
+    }
+
+    #[test]
+    fn loads_rule_from_header() {
+        let replicator = "x = 5, y = 5, rule = B36/S23\n2b3o$bo2bo$o3bo$o2bo$3o!";
+        let loaded = CellPattern::from_rle_with_config(replicator).unwrap();
+        assert_eq!(loaded.config.rule(), Rule::new(vec![3, 6], vec![2, 3]));
+        assert_eq!(loaded.pattern.cells.len(), 12);
+
+        let no_rule = CellPattern::from_rle_with_config("x = 1, y = 1\no!").unwrap();
+        assert_eq!(no_rule.config.rule(), Rule::conway());
+
+        let unknown_rule =
+            CellPattern::from_rle_with_config("x = 1, y = 1, rule = LifeHistory\no!").unwrap();
+        assert_eq!(unknown_rule.config.rule(), Rule::conway());
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        assert!(matches!(
+            CellPattern::from_rle("bob$2bo$3o!"),
+            Err(PatternError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_unexpected_characters() {
+        assert!(matches!(
+            CellPattern::from_rle("x = 3, y = 1\n3z!"),
+            Err(PatternError::Parse { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+/// An outer totalistic rule, written as a rulestring like `B3/S23`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// How many neighbors are required for a dead cell to be born
+    pub birth: Vec<u8>,
+    /// How many neighbors a live cell can live with
+    pub survival: Vec<u8>,
+}
+impl Rule {
+    pub fn new(birth: Vec<u8>, survival: Vec<u8>) -> Self {
+        Self { birth, survival }
+    }
+    /// Conway's game of life, `B3/S23`
+    pub fn conway() -> Self {
+        Self::new(vec![3], vec![2, 3])
+    }
+    /// [Seeds](https://conwaylife.com/wiki/OCA:Seeds), `B2/S`, where every live cell dies each generation
+    pub fn seeds() -> Self {
+        Self::new(vec![2], vec![])
+    }
+    /// Parses a rulestring in B/S notation like `B36/S23`, or in the older S/B notation like `23/36`.
+    ///
+    /// Golly's topology suffixes such as `:T100,100` are ignored. Returns `None` for anything else.
+    pub fn parse(rule: &str) -> Option<Rule> {
+        let rule = rule.split(':').next().unwrap_or_default().trim();
+        let (first, second) = rule.split_once('/')?;
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), Some('S' | 's')) => (&first[1..], &second[1..]),
+            (Some('S' | 's'), Some('B' | 'b')) => (&second[1..], &first[1..]),
+            // Without letters the survival conditions come first
+            _ => (second, first),
+        };
+        Some(Rule::new(parse_counts(birth)?, parse_counts(survival)?))
+    }
+}
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut birth = self.birth.clone();
+        let mut survival = self.survival.clone();
+        birth.sort_unstable();
+        survival.sort_unstable();
+        write!(f, "B")?;
+        for count in birth {
+            write!(f, "{}", count)?;
+        }
+        write!(f, "/S")?;
+        for count in survival {
+            write!(f, "{}", count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses neighbor counts written as digits, like `236`
+fn parse_counts(counts: &str) -> Option<Vec<u8>> {
+    let mut parsed = vec![];
+    for ch in counts.chars() {
+        let count = ch.to_digit(10).filter(|count| *count <= 8)? as u8;
+        if parsed.contains(&count) {
+            return None;
+        }
+        parsed.push(count);
+    }
+    Some(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_notations() {
+        let high_life = Rule::new(vec![3, 6], vec![2, 3]);
+        assert_eq!(Rule::parse("B36/S23"), Some(high_life.clone()));
+        assert_eq!(Rule::parse("b36/s23"), Some(high_life.clone()));
+        assert_eq!(Rule::parse("S23/B36"), Some(high_life.clone()));
+        assert_eq!(Rule::parse("23/36"), Some(high_life.clone()));
+        assert_eq!(Rule::parse("B36/S23:T64,64"), Some(high_life));
+        assert_eq!(Rule::parse("B2/S"), Some(Rule::new(vec![2], vec![])));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!(Rule::parse("LifeHistory"), None);
+        assert_eq!(Rule::parse("B9/S23"), None);
+        assert_eq!(Rule::parse("B33/S23"), None);
+    }
+
+    #[test]
+    fn displays_as_rulestring() {
+        assert_eq!(Rule::conway().to_string(), "B3/S23");
+        assert_eq!(Rule::new(vec![6, 3], vec![3, 2]).to_string(), "B36/S23");
+    }
+}
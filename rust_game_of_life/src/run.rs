@@ -0,0 +1,201 @@
+//! Running a universe until something interesting happens.
+
+use crate::{
+    stability::{Stability, StabilityDetector},
+    universe::Universe,
+    SimulationConfig,
+};
+
+/// A generous limit for runs that have no natural end, so that a pattern that never settles can't hang the app
+pub const DEFAULT_MAX_GENERATIONS: u32 = 100_000;
+
+/// How [`Universe::run_until`] ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The predicate returned `true` at this generation
+    Fired { generation: u64 },
+    /// The predicate never returned `true` within the allowed generations, ending at this generation
+    Timeout { generation: u64 },
+}
+impl RunOutcome {
+    /// Whether the predicate returned `true`
+    pub fn fired(&self) -> bool {
+        matches!(self, RunOutcome::Fired { .. })
+    }
+    /// The generation the run stopped at
+    pub fn generation(&self) -> u64 {
+        match self {
+            RunOutcome::Fired { generation } | RunOutcome::Timeout { generation } => *generation,
+        }
+    }
+}
+
+impl Universe {
+    /// Simulates without spawning entities until `predicate` returns `true` or `max_generations` generations have passed.
+    ///
+    /// The predicate is checked before the first tick and after every tick,
+    /// so a universe that already matches doesn't change at all.
+    pub fn run_until(
+        &mut self,
+        config: &SimulationConfig,
+        mut predicate: impl FnMut(&Universe) -> bool,
+        max_generations: u32,
+    ) -> RunOutcome {
+        if predicate(self) {
+            return RunOutcome::Fired {
+                generation: self.generation(),
+            };
+        }
+        for _ in 0..max_generations {
+            self.step(config);
+            if predicate(self) {
+                return RunOutcome::Fired {
+                    generation: self.generation(),
+                };
+            }
+        }
+        RunOutcome::Timeout {
+            generation: self.generation(),
+        }
+    }
+    /// Simulates without spawning entities until the universe settles down, see [`StabilityDetector`].
+    ///
+    /// Returns how the universe settled, or `None` if it was still changing when the run timed out.
+    /// Spaceships never settle, so use [`DEFAULT_MAX_GENERATIONS`] or a smaller limit rather than an unbounded one.
+    pub fn run_until_stable(
+        &mut self,
+        config: &SimulationConfig,
+        max_generations: u32,
+    ) -> (RunOutcome, Option<Stability>) {
+        let mut detector = StabilityDetector::new();
+        let mut stability = None;
+        let outcome = self.run_until(
+            config,
+            |universe| {
+                stability = detector.observe(universe);
+                stability.is_some()
+            },
+            max_generations,
+        );
+        (outcome, stability)
+    }
+    /// Simulates up to `generations` generations without spawning entities, stopping early once `config` is paused.
+    ///
+    /// Honors [`SimulationConfig::auto_pause_on_stable`] the same way the app does, pausing `config` once the universe settles.
+    /// Returns how the universe settled if it was paused because of that.
+    pub fn run_for(
+        &mut self,
+        config: &mut SimulationConfig,
+        generations: u32,
+    ) -> Option<Stability> {
+        let mut detector = StabilityDetector::new();
+        let mut settled = None;
+        for _ in 0..generations {
+            if config.paused {
+                break;
+            }
+            self.step(config);
+            settled = detector.observe_for_auto_pause(self, config);
+            if settled.is_some() {
+                config.paused = true;
+            }
+        }
+        settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_patterns::CellPattern, utils::Position};
+
+    #[test]
+    fn stops_at_the_first_matching_generation() {
+        let r_pentomino = || {
+            Universe::from_positions(vec![
+                Position::new(1, 0),
+                Position::new(0, 1),
+                Position::new(1, 1),
+                Position::new(1, 2),
+                Position::new(2, 2),
+            ])
+        };
+        let config = SimulationConfig::default();
+        let mut expected = r_pentomino();
+        // Step by hand to find the first generation with more than 10 cells
+        let mut generation = 0;
+        while expected.population() <= 10 {
+            expected.step(&config);
+            generation += 1;
+        }
+
+        let mut universe = r_pentomino();
+        let outcome = universe.run_until(&config, |universe| universe.population() > 10, 100);
+        assert_eq!(outcome, RunOutcome::Fired { generation });
+        assert_eq!(universe.generation(), generation);
+        assert_eq!(universe.population(), expected.population());
+
+        let mut blinker = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let outcome = blinker.run_until(&config, |universe| universe.population() != 3, 20);
+        assert_eq!(outcome, RunOutcome::Timeout { generation: 20 });
+        assert!(!outcome.fired());
+    }
+
+    #[test]
+    fn runs_that_never_end_time_out_at_the_limit() {
+        let config = SimulationConfig::default();
+        let mut glider = Universe::from(&CellPattern::glider());
+        let (outcome, stability) = glider.run_until_stable(&config, 200);
+        assert_eq!(outcome, RunOutcome::Timeout { generation: 200 });
+        assert_eq!(stability, None);
+
+        let mut never = Universe::from(&CellPattern::glider());
+        let outcome = never.run_until(&config, |_| false, 37);
+        assert_eq!(outcome, RunOutcome::Timeout { generation: 37 });
+
+        let mut blinker = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let (outcome, stability) = blinker.run_until_stable(&config, DEFAULT_MAX_GENERATIONS);
+        assert_eq!(outcome, RunOutcome::Fired { generation: 2 });
+        assert_eq!(stability, Some(Stability::Oscillating { period: 2 }));
+    }
+
+    #[test]
+    fn runs_pause_once_stable() {
+        let blinker = || {
+            Universe::from_positions(vec![
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(2, 0),
+            ])
+        };
+        let mut config = SimulationConfig {
+            auto_pause_on_stable: true,
+            ..Default::default()
+        };
+        let mut universe = blinker();
+        assert_eq!(
+            universe.run_for(&mut config, 100),
+            Some(Stability::Oscillating { period: 2 })
+        );
+        assert!(config.paused);
+        // Like in the app, the starting generation isn't observed, so the cycle is noticed a tick later
+        assert_eq!(universe.generation(), 3);
+        // A paused config doesn't run at all
+        assert_eq!(universe.run_for(&mut config, 100), None);
+        assert_eq!(universe.generation(), 3);
+
+        let mut config = SimulationConfig::default();
+        let mut universe = blinker();
+        assert_eq!(universe.run_for(&mut config, 100), None);
+        assert!(!config.paused);
+        assert_eq!(universe.generation(), 100);
+    }
+}
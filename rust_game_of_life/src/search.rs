@@ -0,0 +1,331 @@
+//! Searches for interesting patterns by simulating many small seeds.
+
+use std::collections::HashSet;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cell_patterns::CellPattern,
+    universe::{Bounds, Universe},
+    utils::{Position, SizeInt},
+    SimulationConfig,
+};
+
+/// The longest population cycle that counts as stable, long enough for common oscillators like the pentadecathlon
+const MAX_POPULATION_PERIOD: usize = 15;
+/// How many generations the population has to keep cycling before the pattern counts as stable
+const STABLE_WINDOW: usize = 100;
+
+/// How seeds are picked from the search region
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sampling {
+    /// Tries every possible seed, only feasible for regions of up to 24 cells
+    Exhaustive,
+    /// Tries `count` random seeds where each cell is alive with a 50% chance, reproducible from `seed`
+    Random { count: usize, seed: u64 },
+}
+
+/// What the results of a [`MethuselahSearch`] are sorted by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MethuselahRanking {
+    Lifespan,
+    PeakPopulation,
+}
+
+/// A seed that takes a long time to stabilize
+#[derive(Clone, Debug, PartialEq)]
+pub struct Methuselah {
+    /// The seed, moved so that its bottom left corner is at the origin
+    pub pattern: CellPattern,
+    /// How many generations it takes for the seed to stabilize
+    pub lifespan: u32,
+    /// The largest population reached before stabilizing
+    pub peak_population: usize,
+}
+
+/// Searches a small region for [methuselahs](https://conwaylife.com/wiki/Methuselah), seeds that live for a long time before stabilizing
+#[derive(Clone, Debug)]
+pub struct MethuselahSearch {
+    /// The size of the region seeds are placed in
+    pub region: SizeInt,
+    pub sampling: Sampling,
+    /// Seeds that haven't stabilized after this many generations are discarded
+    pub max_generations: u32,
+    /// Seeds whose population grows beyond this are discarded
+    pub max_population: usize,
+    pub ranking: MethuselahRanking,
+}
+impl MethuselahSearch {
+    /// Runs every seed and returns the ones that stabilized within the budget, best first.
+    ///
+    /// Seeds that are translations of an already tried seed are skipped.
+    /// Seeds are simulated as they're generated, so an exhaustive search never holds more than one seed in memory.
+    pub fn run(&self, config: &SimulationConfig) -> Vec<Methuselah> {
+        // Exhaustive seeds are already unique up to translation, only random ones have to be remembered
+        let remember_tried = matches!(self.sampling, Sampling::Random { .. });
+        let mut tried: HashSet<Vec<Position>> = HashSet::new();
+        let mut results = vec![];
+        for seed in self.seeds() {
+            let pattern = normalized_pattern(&seed);
+            if pattern.cells.is_empty() || (remember_tried && !tried.insert(pattern.cells.clone()))
+            {
+                continue;
+            }
+            if let Some(result) = self.evaluate(pattern, config) {
+                results.push(result);
+            }
+        }
+        match self.ranking {
+            MethuselahRanking::Lifespan => results.sort_by(|a, b| {
+                (b.lifespan, b.peak_population).cmp(&(a.lifespan, a.peak_population))
+            }),
+            MethuselahRanking::PeakPopulation => results.sort_by(|a, b| {
+                (b.peak_population, b.lifespan).cmp(&(a.peak_population, a.lifespan))
+            }),
+        }
+        results
+    }
+    /// Simulates a single seed until it stabilizes, returning `None` if it exceeds the budget.
+    ///
+    /// A seed counts as stable once its population has been cycling with a period of at most 15 generations for 100 generations.
+    /// Comparing populations instead of whole states lets patterns that leave gliders flying away, like the R-pentomino, stabilize.
+    pub fn evaluate(&self, pattern: CellPattern, config: &SimulationConfig) -> Option<Methuselah> {
+        let mut universe = Universe::from_positions(pattern.cells.iter().copied());
+        let mut populations = vec![universe.population()];
+        for _ in 0..self.max_generations {
+            universe.step(config);
+            let population = universe.population();
+            if population > self.max_population {
+                return None;
+            }
+            populations.push(population);
+            if let Some(lifespan) = stabilized_at(&populations) {
+                return Some(Methuselah {
+                    pattern,
+                    lifespan,
+                    peak_population: populations[..=lifespan as usize]
+                        .iter()
+                        .copied()
+                        .max()
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        None
+    }
+    /// The live cell positions of each seed to try, generated one at a time.
+    ///
+    /// Exhaustive seeds are bitmasks of the region's cells. Only the ones touching the bottom and left edges are kept,
+    /// which leaves exactly one seed for every shape that fits in the region.
+    fn seeds(&self) -> Box<dyn Iterator<Item = Vec<Position>>> {
+        let width = self.region.width.max(0);
+        let height = self.region.height.max(0);
+        match self.sampling {
+            Sampling::Exhaustive => {
+                let cell_count = width * height;
+                assert!(
+                    cell_count <= 24,
+                    "region is too large to search exhaustively"
+                );
+                let bottom_row = (1u32 << width) - 1;
+                let left_column = (0..height).fold(0u32, |mask, y| mask | 1 << (y * width));
+                Box::new(
+                    (1..1u32 << cell_count)
+                        .filter(move |mask| mask & bottom_row != 0 && mask & left_column != 0)
+                        .map(move |mask| {
+                            (0..cell_count)
+                                .filter(|bit| mask & (1 << bit) != 0)
+                                .map(|bit| Position::new(bit % width, bit / width))
+                                .collect()
+                        }),
+                )
+            }
+            Sampling::Random { count, seed } => {
+                let region = self.region;
+                let mut rng = StdRng::seed_from_u64(seed);
+                Box::new((0..count).map(move |_| {
+                    Universe::random(region, 0.5, &mut rng)
+                        .cells
+                        .into_keys()
+                        .collect()
+                }))
+            }
+        }
+    }
+}
+
+/// The largest search region [`find_predecessor`] accepts, as every configuration of it is simulated
+const MAX_PREDECESSOR_REGION: usize = 24;
+
+/// Finds a configuration of live cells inside `search_bounds` that evolves into `pattern` in one tick.
+///
+/// Only the cells inside the bounding box of `pattern` have to match, cells around it may be anything.
+/// Cells outside `search_bounds` are treated as dead, so this is a bounded search that only covers
+/// regions of up to 24 cells. Bounds that extend one cell past the pattern cover every cell that can
+/// affect it, anything smaller may miss predecessors.
+pub fn find_predecessor(
+    pattern: &CellPattern,
+    search_bounds: Bounds,
+    config: &SimulationConfig,
+) -> Option<CellPattern> {
+    let target = Universe::from_positions(pattern.cells.iter().copied());
+    if target.cells.is_empty() {
+        return Some(CellPattern::new(vec![]));
+    }
+    let target_bounds = target.bounds();
+    let region: Vec<Position> = (search_bounds.bottom..=search_bounds.top)
+        .flat_map(|y| (search_bounds.left..=search_bounds.right).map(move |x| Position::new(x, y)))
+        .collect();
+    assert!(
+        region.len() <= MAX_PREDECESSOR_REGION,
+        "search bounds are too large to search exhaustively"
+    );
+    (0..1u32 << region.len()).find_map(|mask| {
+        let candidate: Vec<Position> = region
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, pos)| *pos)
+            .collect();
+        let mut universe = Universe::from_positions(candidate.iter().copied());
+        universe.step(config);
+        let matches = (target_bounds.bottom..=target_bounds.top).all(|y| {
+            (target_bounds.left..=target_bounds.right).all(|x| {
+                let pos = Position::new(x, y);
+                universe.state_at(pos) == target.state_at(pos)
+            })
+        });
+        matches.then(|| CellPattern::new(candidate))
+    })
+}
+
+/// Whether `pattern` has no predecessor inside `search_bounds`, making it a local
+/// [Garden of Eden](https://conwaylife.com/wiki/Garden_of_Eden) candidate.
+///
+/// This is a bounded check, see [`find_predecessor`]: unless `search_bounds` extend one cell past the
+/// pattern, it may still have a predecessor outside of them, so `true` doesn't prove that it's an orphan.
+pub fn is_orphan(pattern: &CellPattern, search_bounds: Bounds, config: &SimulationConfig) -> bool {
+    find_predecessor(pattern, search_bounds, config).is_none()
+}
+
+/// Finds the generation the population started cycling at, if it has been cycling for long enough
+fn stabilized_at(populations: &[usize]) -> Option<u32> {
+    let last = populations
+        .len()
+        .checked_sub(STABLE_WINDOW + MAX_POPULATION_PERIOD)?;
+    let period = (1..=MAX_POPULATION_PERIOD).find(|period| {
+        (last..populations.len() - period)
+            .all(|generation| populations[generation] == populations[generation + period])
+    })?;
+    // Walk back to the first generation of the cycle
+    let mut start = last;
+    while start > 0 && populations[start - 1] == populations[start - 1 + period] {
+        start -= 1;
+    }
+    Some(start as u32)
+}
+
+/// Moves the positions so that their bottom left corner is at the origin, sorted for comparisons
+fn normalized_pattern(positions: &[Position]) -> CellPattern {
+    CellPattern::new(positions.to_vec()).normalized()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::Rule;
+
+    fn search(sampling: Sampling) -> MethuselahSearch {
+        MethuselahSearch {
+            region: SizeInt::new(3, 3),
+            sampling,
+            max_generations: 1500,
+            max_population: 500,
+            ranking: MethuselahRanking::Lifespan,
+        }
+    }
+
+    #[test]
+    fn r_pentomino_lives_for_1103_generations() {
+        let r_pentomino = normalized_pattern(&[
+            Position::new(1, 0),
+            Position::new(0, 1),
+            Position::new(1, 1),
+            Position::new(1, 2),
+            Position::new(2, 2),
+        ]);
+        let result = search(Sampling::Exhaustive)
+            .evaluate(r_pentomino, &SimulationConfig::default())
+            .unwrap();
+        assert_eq!(result.lifespan, 1103);
+        assert_eq!(result.peak_population, 319);
+    }
+
+    #[test]
+    #[ignore = "simulates hundreds of seeds, run with --ignored"]
+    fn finds_r_pentomino_in_3x3_region() {
+        let results = search(Sampling::Exhaustive).run(&SimulationConfig::default());
+        let best = &results[0];
+        assert_eq!(best.pattern.cells.len(), 5);
+        assert_eq!(best.peak_population, 319);
+    }
+
+    #[test]
+    fn exhaustive_seeds_are_unique_shapes() {
+        let mut search = search(Sampling::Exhaustive);
+        search.region = SizeInt::new(2, 2);
+        let shapes: HashSet<Vec<Position>> = search
+            .seeds()
+            .map(|seed| normalized_pattern(&seed).cells)
+            .collect();
+        // A cell, 2 dominoes, 2 diagonals, 4 L-trominoes and a block
+        assert_eq!(search.seeds().count(), 10);
+        assert_eq!(shapes.len(), 10);
+    }
+
+    #[test]
+    fn blinker_has_a_predecessor() {
+        let config = SimulationConfig::default();
+        let blinker = CellPattern::new(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let search_bounds = Universe::from_positions(blinker.cells.iter().copied())
+            .bounds()
+            .with_padding(1);
+        let predecessor = find_predecessor(&blinker, search_bounds, &config).unwrap();
+        let mut universe = Universe::from_positions(predecessor.cells);
+        universe.step(&config);
+        assert!(blinker
+            .cells
+            .iter()
+            .all(|pos| universe.cells.contains_key(pos)));
+        assert!(!is_orphan(&blinker, search_bounds, &config));
+    }
+
+    #[test]
+    fn detects_orphans() {
+        // Each cell can only be born with all 8 neighbors alive, including the other cell,
+        // which then can't have survived to be alive next to it
+        let mut config = SimulationConfig::default();
+        config.set_rule(Rule::parse("B8/S").unwrap());
+        let pair = CellPattern::new(vec![Position::new(0, 0), Position::new(1, 0)]);
+        let search_bounds = Universe::from_positions(pair.cells.iter().copied())
+            .bounds()
+            .with_padding(1);
+        assert!(is_orphan(&pair, search_bounds, &config));
+    }
+
+    #[test]
+    fn random_sampling_is_reproducible() {
+        let config = SimulationConfig::default();
+        let first = search(Sampling::Random { count: 20, seed: 7 }).run(&config);
+        let second = search(Sampling::Random { count: 20, seed: 7 }).run(&config);
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+        assert!(first
+            .windows(2)
+            .all(|pair| pair[0].lifespan >= pair[1].lifespan));
+    }
+}
@@ -0,0 +1,148 @@
+//! A compact binary format for saving and sending universes.
+//!
+//! A snapshot starts with the [`MAGIC`] bytes and a [`VERSION`] byte, followed by the number of live cells.
+//! The cells are sorted row by row, and each one is stored as the difference to the previous cell's `x` and `y`,
+//! starting from the origin. Every number is a [LEB128](https://en.wikipedia.org/wiki/LEB128) varint,
+//! with signed differences [zigzag encoded](https://en.wikipedia.org/wiki/Variable-length_quantity#Zigzag_encoding),
+//! so neighboring cells on the same row usually take only two bytes.
+
+use crate::{cell_patterns::PatternError, universe::Universe, utils::Position};
+
+/// The bytes every snapshot starts with
+pub const MAGIC: &[u8; 4] = b"GOLS";
+/// The version of the format written by [`Universe::to_bytes`]
+pub const VERSION: u8 = 1;
+
+impl Universe {
+    /// Encodes the live cells as a binary snapshot, see the [module documentation](self) for the format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut positions: Vec<Position> = self.live_positions().collect();
+        positions.sort_by_key(|pos| (pos.y, pos.x));
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + 2 * positions.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        write_varint(&mut bytes, positions.len() as u64);
+        let mut previous = Position::default();
+        for pos in positions {
+            write_varint(&mut bytes, zigzag(pos.x as i64 - previous.x as i64));
+            write_varint(&mut bytes, zigzag(pos.y as i64 - previous.y as i64));
+            previous = pos;
+        }
+        bytes
+    }
+    /// Decodes a snapshot written by [`Universe::to_bytes`], without spawning any entities.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Universe, PatternError> {
+        let invalid = |message: &str| PatternError::InvalidSnapshot(message.to_string());
+        let rest = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or_else(|| invalid("missing magic bytes"))?;
+        let (version, mut rest) = rest
+            .split_first()
+            .ok_or_else(|| invalid("missing version"))?;
+        if *version != VERSION {
+            return Err(PatternError::InvalidSnapshot(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let count = read_varint(&mut rest).ok_or_else(|| invalid("truncated cell count"))?;
+        // Every cell takes at least two bytes, so don't trust counts that couldn't fit
+        let mut positions = Vec::with_capacity((count as usize).min(rest.len() / 2));
+        let mut previous = Position::default();
+        for _ in 0..count {
+            let (dx, dy) = read_varint(&mut rest)
+                .zip(read_varint(&mut rest))
+                .ok_or_else(|| invalid("truncated cells"))?;
+            let x = i32::try_from(previous.x as i64 + unzigzag(dx))
+                .map_err(|_| invalid("position out of range"))?;
+            let y = i32::try_from(previous.y as i64 + unzigzag(dy))
+                .map_err(|_| invalid("position out of range"))?;
+            previous = Position::new(x, y);
+            positions.push(previous);
+        }
+        if !rest.is_empty() {
+            return Err(invalid("trailing bytes after the cells"));
+        }
+        Ok(Universe::from_positions(positions))
+    }
+}
+
+/// Maps signed numbers to unsigned ones so that small magnitudes stay small: 0, -1, 1, -2... become 0, 1, 2, 3...
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Appends `value` 7 bits at a time, setting the high bit of every byte except the last
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a varint from the start of `bytes` and advances past it, `None` if it's truncated or too long
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[index + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::utils::SizeInt;
+
+    #[test]
+    fn round_trips_large_universes_compactly() {
+        let universe = Universe::random(SizeInt::new(400, 400), 0.4, &mut StdRng::seed_from_u64(3));
+        let bytes = universe.to_bytes();
+        let decoded = Universe::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_csv(), universe.to_csv());
+
+        let mut positions: Vec<&Position> = universe.cells.keys().collect();
+        positions.sort();
+        let json = positions
+            .iter()
+            .map(|pos| format!("{{\"x\":{},\"y\":{}}}", pos.x, pos.y))
+            .collect::<Vec<String>>()
+            .join(",");
+        assert!(bytes.len() * 5 < json.len() + 2);
+    }
+
+    #[test]
+    fn encodes_negative_and_far_apart_cells() {
+        let universe = Universe::from_positions([
+            Position::new(i32::MIN, i32::MAX),
+            Position::new(-1, -1),
+            Position::new(i32::MAX, i32::MIN),
+        ]);
+        let decoded = Universe::from_bytes(&universe.to_bytes()).unwrap();
+        assert_eq!(decoded.to_csv(), universe.to_csv());
+    }
+
+    #[test]
+    fn rejects_malformed_snapshots() {
+        let bytes = Universe::from_positions([Position::new(1, 2)]).to_bytes();
+        assert!(Universe::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Universe::from_bytes(b"JSON").is_err());
+        let mut newer = bytes.clone();
+        newer[MAGIC.len()] = VERSION + 1;
+        assert!(matches!(
+            Universe::from_bytes(&newer),
+            Err(PatternError::InvalidSnapshot(_))
+        ));
+    }
+}
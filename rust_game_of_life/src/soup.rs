@@ -0,0 +1,145 @@
+//! Random 16×16 soups in the shapes used by [apgsearch](https://conwaylife.com/wiki/Apgsearch), for comparing results with community censuses.
+
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    universe::{Cell, Cells, Materials, Universe},
+    utils::Position,
+};
+
+/// The width and height of the random part of a soup
+pub const SOUP_SIZE: i32 = 16;
+
+/// How a soup is mirrored or rotated, named like [apgsearch's symmetries](https://conwaylife.com/wiki/Symmetry)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoupSymmetry {
+    /// No symmetry, a plain 16×16 soup
+    C1,
+    /// 180° rotational symmetry around the center of a cell, 31×31 cells
+    C2_1,
+    /// Mirrored across a row that belongs to both halves, 16×31 cells
+    D2Plus1,
+}
+impl SoupSymmetry {
+    /// All supported symmetries
+    pub const ALL: [SoupSymmetry; 3] =
+        [SoupSymmetry::C1, SoupSymmetry::C2_1, SoupSymmetry::D2Plus1];
+    /// The apgsearch code of the symmetry, like `D2_+1`
+    pub fn code(&self) -> &'static str {
+        match self {
+            SoupSymmetry::C1 => "C1",
+            SoupSymmetry::C2_1 => "C2_1",
+            SoupSymmetry::D2Plus1 => "D2_+1",
+        }
+    }
+    /// The copies of a position in the random 16×16 part that make up the symmetric soup
+    fn images(&self, pos: Position) -> Vec<Position> {
+        // The last row and column of the random part lie on the axis or center of symmetry
+        let far = 2 * (SOUP_SIZE - 1);
+        match self {
+            SoupSymmetry::C1 => vec![pos],
+            SoupSymmetry::C2_1 => vec![pos, Position::new(far - pos.x, far - pos.y)],
+            SoupSymmetry::D2Plus1 => vec![pos, Position::new(pos.x, far - pos.y)],
+        }
+    }
+}
+impl FromStr for SoupSymmetry {
+    type Err = String;
+
+    /// Parses a symmetry from its apgsearch code, like `C2_1`
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        SoupSymmetry::ALL
+            .iter()
+            .find(|symmetry| symmetry.code() == code)
+            .copied()
+            .ok_or_else(|| format!("unsupported soup symmetry '{}'", code))
+    }
+}
+
+impl Universe {
+    /// Generates a 50% density soup with the given apgsearch symmetry code, reproducible from `seed`.
+    ///
+    /// The soup shapes follow apgsearch, with the random part in the 16×16 square at the origin,
+    /// but cells are rolled with this crate's seeded random number generator instead of apgsearch's SHA-256 hashing,
+    /// so a seed doesn't produce the same soup as the apgsearch seed with the same digits.
+    pub fn standard_soup(
+        commands: &mut Commands,
+        materials: Materials,
+        seed: u64,
+        symmetry: &str,
+    ) -> Result<Self, String> {
+        let mut universe = Universe::new(soup_cells(seed, symmetry.parse()?), materials);
+        universe.sync_entities(commands);
+        Ok(universe)
+    }
+}
+
+/// Rolls the random part row by row from the bottom left and fills in its symmetric copies
+fn soup_cells(seed: u64, symmetry: SoupSymmetry) -> Cells {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut cells = Cells::new();
+    for y in 0..SOUP_SIZE {
+        for x in 0..SOUP_SIZE {
+            if rng.gen_bool(0.5) {
+                for pos in symmetry.images(Position::new(x, y)) {
+                    cells.insert(pos, Cell::default());
+                }
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::{system::CommandQueue, world::World};
+
+    use super::*;
+
+    fn soup_positions(seed: u64, symmetry: &str) -> Vec<Position> {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let soup =
+            Universe::standard_soup(&mut commands, Materials::default(), seed, symmetry).unwrap();
+        let mut positions: Vec<Position> = soup.cells.keys().copied().collect();
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn same_seed_and_symmetry_give_the_same_soup() {
+        for symmetry in SoupSymmetry::ALL {
+            assert_eq!(
+                soup_positions(7, symmetry.code()),
+                soup_positions(7, symmetry.code())
+            );
+        }
+        assert_ne!(soup_positions(7, "C1"), soup_positions(8, "C1"));
+    }
+
+    #[test]
+    fn soups_have_their_symmetry() {
+        let c1 = soup_positions(3, "C1");
+        assert!(c1
+            .iter()
+            .all(|pos| (0..16).contains(&pos.x) && (0..16).contains(&pos.y)));
+
+        let c2 = soup_positions(3, "C2_1");
+        assert!(c2
+            .iter()
+            .all(|pos| c2.contains(&Position::new(30 - pos.x, 30 - pos.y))));
+
+        let d2 = soup_positions(3, "D2_+1");
+        assert!(d2
+            .iter()
+            .all(|pos| pos.x < 16 && d2.contains(&Position::new(pos.x, 30 - pos.y))));
+
+        // The random part is rolled the same way regardless of the symmetry
+        assert!(c1.iter().all(|pos| c2.contains(pos) && d2.contains(pos)));
+        assert!("G1".parse::<SoupSymmetry>().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Detecting when a universe has settled down, see [`SimulationConfig::auto_pause_on_stable`](crate::SimulationConfig::auto_pause_on_stable).
+
+use std::{
+    collections::{hash_map::DefaultHasher, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    universe::{Bounds, Universe},
+    utils::Position,
+    SimulationConfig,
+};
+
+/// The longest cycle [`StabilityDetector`] can find
+pub const MAX_DETECTED_PERIOD: usize = 30;
+
+/// How a universe settled down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stability {
+    /// Every cell died
+    Dead,
+    /// Nothing changes between generations
+    StillLife,
+    /// The same states keep repeating
+    Oscillating { period: usize },
+}
+
+/// Remembers the most recent states of a universe to notice when they start repeating.
+///
+/// States are compared by hash, which keeps the memory use tiny but could in theory report a cycle that isn't there.
+/// Patterns that move, like gliders, never repeat exactly and aren't detected.
+#[derive(Clone, Debug, Default)]
+pub struct StabilityDetector {
+    /// Hashes of the latest states, newest last
+    recent: VecDeque<u64>,
+}
+impl StabilityDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Records the current state of `universe`, returning how it settled if the state was seen within the last [`MAX_DETECTED_PERIOD`] observations.
+    ///
+    /// Should be called once per generation, including the first one.
+    pub fn observe(&mut self, universe: &Universe) -> Option<Stability> {
+        if universe.population() == 0 {
+            return Some(Stability::Dead);
+        }
+        let hash = state_hash(universe);
+        let period = self
+            .recent
+            .iter()
+            .rev()
+            .position(|recent| *recent == hash)
+            .map(|index| index + 1);
+        self.recent.push_back(hash);
+        if self.recent.len() > MAX_DETECTED_PERIOD {
+            self.recent.pop_front();
+        }
+        match period? {
+            1 => Some(Stability::StillLife),
+            period => Some(Stability::Oscillating { period }),
+        }
+    }
+    /// Observes `universe` like [`StabilityDetector::observe`], but only if [`SimulationConfig::auto_pause_on_stable`] is set.
+    ///
+    /// Returns how the universe settled once the run should pause, after which the detector starts over.
+    /// Skipping the observation otherwise saves sorting and hashing every live cell each tick.
+    pub fn observe_for_auto_pause(
+        &mut self,
+        universe: &Universe,
+        config: &SimulationConfig,
+    ) -> Option<Stability> {
+        if !config.auto_pause_on_stable {
+            return None;
+        }
+        let settled = self.observe(universe)?;
+        self.reset();
+        Some(settled)
+    }
+    /// Forgets every state, for example after the universe was edited.
+    pub fn reset(&mut self) {
+        self.recent.clear();
+    }
+}
+
+impl Universe {
+    /// The period of the cycle the universe settles into, or `None` if it doesn't repeat within `max_period` generations.
+    ///
+    /// Unlike [`StabilityDetector`], whole states are compared instead of hashes, so a reported period is always real.
+    /// Only a single reference state is kept. A fork first runs for `max_period` generations so that short transients die out, then its state is kept and compared to each of the next `max_period` generations.
+    /// Cells have to be back at the same positions, so spaceships only repeat in a wrapping topology. Still lifes have a period of 1.
+    pub fn find_period(&self, config: &SimulationConfig, max_period: u32) -> Option<u32> {
+        let mut universe = self.fork();
+        universe.advance(max_period, config);
+        let reference = stored_state(&universe);
+        for period in 1..=max_period {
+            universe.step(config);
+            if universe.cells.len() == reference.1.len() && stored_state(&universe) == reference {
+                return Some(period);
+            }
+        }
+        None
+    }
+}
+
+/// The background and the sorted stored cells, which pin down the live cells without walking an alive background
+fn stored_state(universe: &Universe) -> (Option<Bounds>, Vec<Position>) {
+    let mut positions: Vec<Position> = universe.cells.keys().copied().collect();
+    positions.sort();
+    (universe.background(), positions)
+}
+
+/// Hashes the live cells in a fixed order
+fn state_hash(universe: &Universe) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    stored_state(universe).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_patterns::CellPattern, topology::Topology, universe::Bounds};
+
+    #[test]
+    fn blinkers_auto_pause_after_a_cycle() {
+        let mut config = SimulationConfig {
+            auto_pause_on_stable: true,
+            ..Default::default()
+        };
+        let mut universe = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let mut detector = StabilityDetector::new();
+        assert_eq!(detector.observe_for_auto_pause(&universe, &config), None);
+        universe.step(&config);
+        assert_eq!(detector.observe_for_auto_pause(&universe, &config), None);
+        universe.step(&config);
+        assert_eq!(
+            detector.observe_for_auto_pause(&universe, &config),
+            Some(Stability::Oscillating { period: 2 })
+        );
+
+        // Nothing is observed while auto-pausing is off
+        config.auto_pause_on_stable = false;
+        for _ in 0..4 {
+            universe.step(&config);
+            assert_eq!(detector.observe_for_auto_pause(&universe, &config), None);
+        }
+    }
+
+    #[test]
+    fn still_lifes_and_empty_universes_are_stable() {
+        let config = SimulationConfig::default();
+        let mut detector = StabilityDetector::new();
+        let mut block = Universe::from(&CellPattern::new(vec![
+            Position::new(0, 0),
+            Position::new(0, 1),
+            Position::new(1, 0),
+            Position::new(1, 1),
+        ]));
+        assert_eq!(detector.observe(&block), None);
+        block.step(&config);
+        assert_eq!(detector.observe(&block), Some(Stability::StillLife));
+
+        let mut glider = Universe::from(&CellPattern::glider());
+        detector.reset();
+        for _ in 0..40 {
+            assert_eq!(detector.observe(&glider), None);
+            glider.step(&config);
+        }
+        assert_eq!(
+            detector.observe(&Universe::default()),
+            Some(Stability::Dead)
+        );
+    }
+
+    #[test]
+    fn finds_exact_periods() {
+        let config = SimulationConfig::default();
+        let pulsar = CellPattern::from_rle(
+            "x = 13, y = 13\n2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+        )
+        .unwrap();
+        assert_eq!(Universe::from(&pulsar).find_period(&config, 10), Some(3));
+        assert_eq!(Universe::from(&pulsar).find_period(&config, 2), None);
+
+        // A glider on an 8×8 torus is back where it started after crossing it once
+        let torus = SimulationConfig {
+            topology: Topology::Toroidal(Bounds {
+                top: 7,
+                right: 7,
+                bottom: 0,
+                left: 0,
+            }),
+            ..Default::default()
+        };
+        let glider = Universe::from(&CellPattern::glider());
+        assert_eq!(glider.find_period(&torus, 31), None);
+        assert_eq!(glider.find_period(&torus, 40), Some(32));
+        assert_eq!(glider.find_period(&config, 40), None);
+        assert_eq!(glider.generation(), 0);
+    }
+}
@@ -0,0 +1,82 @@
+//! Per-generation statistics for plotting a run in other tools.
+
+use std::fmt::Write as _;
+
+use crate::{universe::Universe, SimulationConfig};
+
+/// The first line of every CSV file written by [`stats_to_csv`]
+const STATS_CSV_HEADER: &str = "generation,births,deaths,population";
+
+/// What happened during a single tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickStats {
+    /// The [`Universe::generation`] after the tick
+    pub generation: u64,
+    pub births: usize,
+    pub deaths: usize,
+    /// The number of live cells after the tick
+    pub population: usize,
+}
+
+impl Universe {
+    /// Simulates `generations` generations without spawning entities, returning the statistics of every tick.
+    pub fn run_collecting_stats(
+        &mut self,
+        generations: u32,
+        config: &SimulationConfig,
+    ) -> Vec<TickStats> {
+        (0..generations)
+            .map(|_| {
+                let delta = self.step(config);
+                TickStats {
+                    generation: self.generation(),
+                    births: delta.births.len(),
+                    deaths: delta.deaths.len(),
+                    population: self.population(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Writes the statistics as CSV with a `generation,births,deaths,population` header, one row per tick.
+pub fn stats_to_csv(stats: &[TickStats]) -> String {
+    let mut csv = String::from(STATS_CSV_HEADER);
+    csv.push('\n');
+    for tick in stats {
+        // Writing to a string can't fail
+        let _ = writeln!(
+            csv,
+            "{},{},{},{}",
+            tick.generation, tick.births, tick.deaths, tick.population
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_patterns::CellPattern, utils::Position};
+
+    #[test]
+    fn collects_a_row_per_generation() {
+        let mut blinker = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let stats = blinker.run_collecting_stats(50, &SimulationConfig::default());
+        assert_eq!(stats.len(), 50);
+        assert!(stats
+            .iter()
+            .all(|tick| tick.births == 2 && tick.deaths == 2 && tick.population == 3));
+
+        let mut glider = Universe::from(&CellPattern::glider());
+        let csv = stats_to_csv(&glider.run_collecting_stats(2, &SimulationConfig::default()));
+        assert_eq!(
+            csv,
+            "generation,births,deaths,population\n1,2,2,5\n2,2,2,5\n"
+        );
+    }
+}
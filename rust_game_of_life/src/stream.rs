@@ -0,0 +1,79 @@
+//! Streams the frames of a running universe, for example to send them to clients of a server.
+
+use futures::{stream, Stream};
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::{
+    universe::{TickDelta, Universe},
+    SimulationConfig,
+};
+
+/// A single generation of a streamed universe
+#[derive(Clone, Debug, PartialEq)]
+pub struct TickFrame {
+    /// The generation the universe is at after the tick
+    pub generation: u64,
+    pub population: usize,
+    /// The births and deaths of the tick
+    pub delta: TickDelta,
+}
+
+impl Universe {
+    /// Turns the universe into an endless stream of frames, one per generation, without spawning any entities.
+    ///
+    /// Frames are produced at most once per [`SimulationConfig::tick_interval`], starting right away,
+    /// and as fast as they're polled if it's `None`. The universe is only stepped when the next frame is polled,
+    /// so a slow consumer pauses the simulation instead of letting frames pile up.
+    /// [`SimulationConfig::paused`] is ignored, stop polling the stream to pause it.
+    ///
+    /// Must be polled within a Tokio runtime unless the tick interval is `None`.
+    pub fn stream(self, config: SimulationConfig) -> impl Stream<Item = TickFrame> {
+        let interval = config.tick_interval().map(|period| {
+            let mut interval = time::interval(period);
+            // Don't catch up with a burst of frames after the consumer was slow
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval
+        });
+        stream::unfold(
+            (self, config, interval),
+            |(mut universe, config, mut interval)| async move {
+                if let Some(interval) = &mut interval {
+                    interval.tick().await;
+                }
+                let delta = universe.step(&config);
+                let frame = TickFrame {
+                    generation: universe.generation(),
+                    population: universe.population(),
+                    delta,
+                };
+                Some((frame, (universe, config, interval)))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor, StreamExt};
+
+    use super::*;
+    use crate::{cell_patterns::CellPattern, SpeedPreset};
+
+    #[test]
+    fn streams_one_frame_per_generation() {
+        let config = SimulationConfig {
+            speed_preset: Some(SpeedPreset::Max),
+            ..Default::default()
+        };
+        let mut expected = Universe::from_pattern(&CellPattern::glider(), None);
+        let mut frames = Box::pin(expected.fork().stream(config.clone()));
+        executor::block_on(async {
+            for generation in 1..=4 {
+                let frame = frames.next().await.unwrap();
+                assert_eq!(frame.generation, generation);
+                assert_eq!(frame.delta, expected.step(&config));
+                assert_eq!(frame.population, 5);
+            }
+        });
+    }
+}
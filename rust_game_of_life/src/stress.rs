@@ -0,0 +1,142 @@
+//! Named boards for load testing, so that benchmarks and performance reports can refer to the same work.
+//!
+//! Every scenario is built without spawning any entities, and the random ones are reproducible from a seed.
+
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    cell_patterns::CellPattern,
+    universe::Universe,
+    utils::{Position, SizeInt},
+};
+
+/// The width and height of the [`StressKind::DenseSoup`] board
+pub const DENSE_SOUP_SIZE: i32 = 512;
+/// How many guns [`StressKind::GliderStream`] places side by side
+pub const GLIDER_GUNS: i32 = 8;
+/// How many R-pentominoes [`StressKind::MethuselahField`] scatters
+pub const METHUSELAHS: usize = 64;
+/// The width and height of the square the [`StressKind::MethuselahField`] R-pentominoes are scattered in
+pub const METHUSELAH_FIELD_SIZE: i32 = 1024;
+
+/// A scenario built by [`Universe::stress_test`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StressKind {
+    /// A 512×512 soup at 50% density, with births and deaths everywhere for hundreds of generations
+    DenseSoup,
+    /// A row of Gosper glider guns, a steady stream of births and a board that keeps growing. Ignores the seed.
+    GliderStream,
+    /// R-pentominoes scattered over a 1024×1024 square, chaotic growth that spreads until the reactions run into each other
+    MethuselahField,
+}
+impl StressKind {
+    /// All stress scenarios
+    pub const ALL: [StressKind; 3] = [
+        StressKind::DenseSoup,
+        StressKind::GliderStream,
+        StressKind::MethuselahField,
+    ];
+    /// The name of the scenario for benchmark reports, like `dense-soup`
+    pub fn name(&self) -> &'static str {
+        match self {
+            StressKind::DenseSoup => "dense-soup",
+            StressKind::GliderStream => "glider-stream",
+            StressKind::MethuselahField => "methuselah-field",
+        }
+    }
+}
+impl FromStr for StressKind {
+    type Err = String;
+
+    /// Parses a scenario from its name, like `glider-stream`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        StressKind::ALL
+            .iter()
+            .find(|kind| kind.name() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown stress scenario '{}'", name))
+    }
+}
+
+impl Universe {
+    /// Builds the board of a stress scenario, the same for the same `kind` and `seed`.
+    pub fn stress_test(kind: StressKind, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        match kind {
+            StressKind::DenseSoup => Universe::random(
+                SizeInt::new(DENSE_SOUP_SIZE, DENSE_SOUP_SIZE),
+                0.5,
+                &mut rng,
+            ),
+            StressKind::GliderStream => {
+                let (_, gun) = CellPattern::by_name("Gosper glider gun")
+                    .expect("the catalog has a Gosper glider gun");
+                // The gliders head down and to the right, so every stream passes below the guns to its right
+                let spacing = 48;
+                Universe::from_positions(
+                    (0..GLIDER_GUNS)
+                        .flat_map(|index| gun.translated(Position::new(index * spacing, 0)).cells),
+                )
+            }
+            StressKind::MethuselahField => {
+                let (_, r_pentomino) =
+                    CellPattern::by_name("R-pentomino").expect("the catalog has an R-pentomino");
+                let mut positions = vec![];
+                for _ in 0..METHUSELAHS {
+                    let mut pattern = r_pentomino.clone();
+                    for _ in 0..rng.gen_range(0..4) {
+                        pattern = pattern.rotated_clockwise();
+                    }
+                    let offset = Position::new(
+                        rng.gen_range(0..METHUSELAH_FIELD_SIZE),
+                        rng.gen_range(0..METHUSELAH_FIELD_SIZE),
+                    );
+                    positions.extend(pattern.translated(offset).cells);
+                }
+                Universe::from_positions(positions)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationConfig;
+
+    #[test]
+    fn scenarios_are_reproducible() {
+        for kind in StressKind::ALL {
+            let first = Universe::stress_test(kind, 9);
+            let second = Universe::stress_test(kind, 9);
+            assert!(first.population() > 0, "{:?}", kind);
+            assert_eq!(first.population(), second.population(), "{:?}", kind);
+            assert!(first.diff(&second).is_empty(), "{:?}", kind);
+            assert_eq!(kind.name().parse(), Ok(kind));
+        }
+        assert_eq!(
+            Universe::stress_test(StressKind::GliderStream, 1).population(),
+            GLIDER_GUNS as usize * 36
+        );
+        assert!(!Universe::stress_test(StressKind::DenseSoup, 1)
+            .diff(&Universe::stress_test(StressKind::DenseSoup, 2))
+            .is_empty());
+        assert!("ackermann".parse::<StressKind>().is_err());
+    }
+
+    #[test]
+    fn glider_streams_never_interfere() {
+        let config = SimulationConfig::default();
+        let (_, gun) = CellPattern::by_name("Gosper glider gun").unwrap();
+        let mut single = Universe::from(&gun);
+        let mut stream = Universe::stress_test(StressKind::GliderStream, 0);
+        single.advance(300, &config);
+        stream.advance(300, &config);
+        assert_eq!(
+            stream.population(),
+            GLIDER_GUNS as usize * single.population()
+        );
+    }
+}
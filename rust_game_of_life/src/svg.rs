@@ -0,0 +1,95 @@
+//! Standalone [SVG](https://developer.mozilla.org/en-US/docs/Web/SVG) snapshots for embedding a board in web pages and docs.
+
+use std::fmt::Write as _;
+
+use crate::{
+    universe::{Bounds, Universe},
+    utils::{CoordinateConvention, Position},
+};
+
+/// The fill of the background
+const SVG_BACKGROUND: &str = "#ffffff";
+/// The fill of live cells
+const SVG_CELL: &str = "#000000";
+
+impl Universe {
+    /// Draws the live cells inside `bounds` as an SVG image, with a `<rect class="cell">` per live cell on a white background.
+    ///
+    /// Every cell is `cell_size` pixels wide and the top row of `bounds` is drawn at the top of the image.
+    /// Empty bounds give an empty image of zero size, which is still a valid SVG document.
+    pub fn to_svg(&self, bounds: Bounds, cell_size: u32) -> String {
+        let (columns, rows) = if bounds.is_empty() {
+            (0, 0)
+        } else {
+            (
+                (bounds.right - bounds.left + 1) as u32,
+                (bounds.top - bounds.bottom + 1) as u32,
+            )
+        };
+        let (width, height) = (columns * cell_size, rows * cell_size);
+        let mut positions: Vec<Position> = self
+            .live_positions()
+            .filter(|pos| bounds.contains(*pos))
+            .collect();
+        positions.sort();
+
+        // Writing to a string can't fail
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            width, height, SVG_BACKGROUND
+        );
+        for pos in positions {
+            let _ = writeln!(
+                svg,
+                r#"<rect class="cell" x="{}" y="{}" width="{3}" height="{3}" fill="{}"/>"#,
+                (pos.x - bounds.left) as u32 * cell_size,
+                (bounds.top - pos.y) as u32 * cell_size,
+                SVG_CELL,
+                cell_size,
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+    /// Like [`Universe::to_svg`], but with the positions converted to the given coordinate convention first.
+    ///
+    /// `bounds` are in the converted coordinates, and a y-down convention draws the board upside down.
+    pub fn to_svg_with(
+        &self,
+        bounds: Bounds,
+        cell_size: u32,
+        convention: CoordinateConvention,
+    ) -> String {
+        self.exported(convention).to_svg(bounds, cell_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell_patterns::CellPattern;
+
+    #[test]
+    fn draws_a_rect_per_live_cell() {
+        let glider = Universe::from(&CellPattern::glider());
+        let svg = glider.to_svg(glider.bounds().with_padding(1), 10);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r#"width="50" height="50""#));
+        assert_eq!(svg.matches(r#"<rect class="cell""#).count(), 5);
+        // The top cell of the glider is in the second row and third column of the padded bounds
+        assert!(svg.contains(r#"<rect class="cell" x="20" y="10""#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        let empty = Universe::default();
+        let svg = empty.to_svg(empty.bounds(), 10);
+        assert!(svg.contains(r#"viewBox="0 0 0 0""#));
+        assert_eq!(svg.matches("<rect").count(), 1);
+    }
+}
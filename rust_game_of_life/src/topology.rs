@@ -0,0 +1,151 @@
+use crate::{
+    universe::Bounds,
+    utils::{Neighborhood, Position},
+};
+
+/// The shape of the space cells live in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// An endless plane, cells can be born anywhere
+    #[default]
+    Infinite,
+    /// A finite rectangle where neighbors outside of the bounds count as dead, like a petri dish
+    Bounded(Bounds),
+    /// A rectangle whose opposite edges are connected, so neighbors outside of the bounds wrap around.
+    ///
+    /// The rectangle is declared up front and doesn't depend on the live cells, which are still stored sparsely.
+    /// Neighbor positions are wrapped into it before looking them up.
+    Toroidal(Bounds),
+    /// A finite rectangle with a custom policy for neighbors outside of it
+    Region(Bounds, OutOfBoundsPolicy),
+}
+impl Topology {
+    /// The region cells are confined to, `None` for an infinite plane
+    pub fn bounds(&self) -> Option<&Bounds> {
+        match self {
+            Topology::Infinite => None,
+            Topology::Bounded(bounds)
+            | Topology::Toroidal(bounds)
+            | Topology::Region(bounds, _) => Some(bounds),
+        }
+    }
+    /// How neighbors outside of the bounds are treated, `None` for an infinite plane
+    pub fn out_of_bounds_policy(&self) -> Option<OutOfBoundsPolicy> {
+        match self {
+            Topology::Infinite => None,
+            Topology::Bounded(_) => Some(OutOfBoundsPolicy::Dead),
+            Topology::Toroidal(_) => Some(OutOfBoundsPolicy::Wrap),
+            Topology::Region(_, policy) => Some(*policy),
+        }
+    }
+    /// Whether a cell at `pos` is part of the space
+    pub fn contains(&self, pos: Position) -> bool {
+        match self.bounds() {
+            Some(bounds) => bounds.contains(pos),
+            None => true,
+        }
+    }
+    /// Finds out what a neighbor at `pos` refers to, applying the out-of-bounds policy if it's outside of the bounds
+    pub fn resolve(&self, pos: Position) -> NeighborLookup {
+        let (bounds, policy) = match (self.bounds(), self.out_of_bounds_policy()) {
+            (Some(bounds), Some(policy)) if !bounds.contains(pos) => (bounds, policy),
+            _ => return NeighborLookup::At(pos),
+        };
+        match policy {
+            OutOfBoundsPolicy::Dead => NeighborLookup::Dead,
+            OutOfBoundsPolicy::Alive => NeighborLookup::Alive,
+            OutOfBoundsPolicy::Wrap => NeighborLookup::At(wrap(pos, bounds)),
+            OutOfBoundsPolicy::Mirror => NeighborLookup::At(mirror(pos, bounds)),
+        }
+    }
+    /// Wraps `pos` into the bounds of a space whose edges wrap around, such as a position drawn just past the edge of a torus.
+    ///
+    /// Positions in any other space are returned unchanged.
+    pub fn wrap_position(&self, pos: Position) -> Position {
+        match (self.bounds(), self.out_of_bounds_policy()) {
+            (Some(bounds), Some(OutOfBoundsPolicy::Wrap)) if !bounds.is_empty() => {
+                wrap(pos, bounds)
+            }
+            _ => pos,
+        }
+    }
+    /// The neighbors of `pos` that are cells in the space, wrapped or mirrored back into the bounds if needed
+    pub fn neighbors(&self, pos: Position) -> Vec<Position> {
+        self.neighbors_in(pos, Neighborhood::Moore)
+    }
+    /// Like [`Topology::neighbors`], but for the given neighborhood
+    pub fn neighbors_in(&self, pos: Position, neighborhood: Neighborhood) -> Vec<Position> {
+        pos.neighbors_in(neighborhood)
+            .into_iter()
+            .filter_map(|neighbor_pos| match self.resolve(neighbor_pos) {
+                NeighborLookup::At(resolved) => Some(resolved),
+                NeighborLookup::Dead | NeighborLookup::Alive => None,
+            })
+            .collect()
+    }
+}
+
+/// How neighbors outside of a finite topology's bounds are treated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutOfBoundsPolicy {
+    /// Neighbors outside of the bounds are always dead
+    Dead,
+    /// Neighbors outside of the bounds are always alive, so edge cells act as if they were surrounded
+    Alive,
+    /// Neighbors outside of the bounds wrap around to the opposite edge
+    Wrap,
+    /// Neighbors outside of the bounds are reflected back across the nearest edge, as if the edge was a mirror
+    Mirror,
+}
+
+/// What a neighbor position refers to in a topology
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NeighborLookup {
+    /// The cell at this position, which may differ from the neighbor position if it was wrapped or mirrored
+    At(Position),
+    /// A neighbor outside of the bounds that is always dead
+    Dead,
+    /// A neighbor outside of the bounds that is always alive
+    Alive,
+}
+
+/// Wraps `pos` into `bounds` as if the opposite edges of the bounds were connected
+fn wrap(pos: Position, bounds: &Bounds) -> Position {
+    let width = bounds.right - bounds.left + 1;
+    let height = bounds.top - bounds.bottom + 1;
+    Position::new(
+        bounds.left + (pos.x - bounds.left).rem_euclid(width),
+        bounds.bottom + (pos.y - bounds.bottom).rem_euclid(height),
+    )
+}
+
+/// Reflects `pos` back into `bounds`, with the mirror lying just outside of the edge cells
+fn mirror(pos: Position, bounds: &Bounds) -> Position {
+    fn reflect(value: i32, min: i32, max: i32) -> i32 {
+        if value < min {
+            2 * min - value - 1
+        } else if value > max {
+            2 * max - value + 1
+        } else {
+            value
+        }
+    }
+    Position::new(
+        reflect(pos.x, bounds.left, bounds.right),
+        reflect(pos.y, bounds.bottom, bounds.top),
+    )
+}
+
+/// The positions on the edge of `bounds`
+pub(crate) fn edge_positions(bounds: &Bounds) -> Vec<Position> {
+    let mut positions = vec![];
+    for y in bounds.bottom..=bounds.top {
+        for x in bounds.left..=bounds.right {
+            let pos = Position::new(x, y);
+            if x == bounds.left || x == bounds.right || y == bounds.bottom || y == bounds.top {
+                positions.push(pos);
+            }
+        }
+    }
+    positions
+}
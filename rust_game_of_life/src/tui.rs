@@ -0,0 +1,139 @@
+//! A minimal terminal renderer for running a universe without a game engine.
+//!
+//! Controls: `space` pauses and resumes, `s` steps one generation while paused, `q` or `esc` quits.
+
+use std::{
+    io::{self, Write},
+    time::Instant,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    stability::StabilityDetector,
+    universe::{CellState, Universe},
+    utils::Position,
+    SimulationConfig,
+};
+
+/// Runs the simulation in the terminal until the user quits.
+///
+/// The universe must have been created without a game engine, for example with [`Universe::random`].
+/// Fails with [`io::ErrorKind::InvalidInput`] if the config doesn't [validate](SimulationConfig::validate).
+pub fn run(universe: Universe, config: &SimulationConfig) -> io::Result<()> {
+    config
+        .validate()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run_loop(&mut stdout, universe, config);
+
+    // Restore the terminal even if the loop failed
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn run_loop(
+    stdout: &mut impl Write,
+    mut universe: Universe,
+    config: &SimulationConfig,
+) -> io::Result<()> {
+    let mut paused = config.paused;
+    let mut stability = StabilityDetector::new();
+    stability.observe_for_auto_pause(&universe, config);
+    let mut last_tick = Instant::now();
+    let mut needs_redraw = true;
+    loop {
+        if needs_redraw {
+            draw(stdout, &universe, config, paused)?;
+            needs_redraw = false;
+        }
+
+        // The fastest speed preset ticks as often as possible
+        let tick_interval = config.tick_interval().unwrap_or_default();
+        let timeout = tick_interval.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(' ') => {
+                        paused = !paused;
+                        last_tick = Instant::now();
+                        needs_redraw = true;
+                    }
+                    KeyCode::Char('s') if paused => {
+                        universe.step(config);
+                        stability.observe_for_auto_pause(&universe, config);
+                        needs_redraw = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !paused && last_tick.elapsed() >= tick_interval {
+            universe.step(config);
+            if stability
+                .observe_for_auto_pause(&universe, config)
+                .is_some()
+            {
+                paused = true;
+            }
+            last_tick = Instant::now();
+            needs_redraw = true;
+        }
+    }
+}
+
+/// Draws the padded bounds of the universe, clipped to the terminal, with a status line at the bottom.
+fn draw(
+    stdout: &mut impl Write,
+    universe: &Universe,
+    config: &SimulationConfig,
+    paused: bool,
+) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let bounds = config.render_bounds(universe);
+    queue!(stdout, terminal::Clear(ClearType::All))?;
+
+    let visible_rows = rows.saturating_sub(1) as usize;
+    for (row, y) in (bounds.bottom..=bounds.top)
+        .rev()
+        .take(visible_rows)
+        .enumerate()
+    {
+        let line: String = (bounds.left..=bounds.right)
+            .take(columns as usize)
+            .map(|x| {
+                if universe.state_at(Position::new(x, y)) == CellState::Alive {
+                    '█'
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+        queue!(stdout, cursor::MoveTo(0, row as u16), Print(line))?;
+    }
+
+    let status = format!(
+        "Generation: {} | Population: {} | {} | [space] pause  [s] step  [q] quit",
+        universe.generation(),
+        universe.population(),
+        if paused { "Paused" } else { "Running" },
+    );
+    queue!(
+        stdout,
+        cursor::MoveTo(0, rows.saturating_sub(1)),
+        Print(status)
+    )?;
+    stdout.flush()
+}
@@ -1,23 +1,68 @@
 // TODO: Decouple from game engine
 
-use std::{collections::HashMap, fmt, i32::MAX};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt,
+};
 
 use bevy::prelude::*;
-use rand::random;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::utils::{Position, SizeFloat, SizeInt};
+use crate::{
+    cell_patterns::CellPattern,
+    topology::{edge_positions, NeighborLookup, OutOfBoundsPolicy, Topology},
+    utils::{
+        CoordinateConvention, InvalidSize, NeighborWeights, Neighborhood, Position, SizeFloat,
+        SizeInt, NEIGHBOR_OFFSETS,
+    },
+    SimulationConfig,
+};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Cell {
-    pub entity: Entity,
+    /// The entity rendering the cell, `None` for cells that were created without a game engine
+    pub entity: Option<Entity>,
 }
 impl Cell {
     fn new(entity: Entity) -> Self {
-        Self { entity }
+        Self {
+            entity: Some(entity),
+        }
+    }
+    /// What kind of live cell this is
+    pub fn kind(&self) -> CellKind {
+        CellKind::Alive
+    }
+}
+
+/// Whether a cell is alive or dead, see [`Universe::state_at`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CellState {
+    Dead,
+    Alive,
+}
+impl CellState {
+    /// The state a cell is in after being clicked or toggled by an edit
+    pub fn toggle(self) -> CellState {
+        match self {
+            CellState::Dead => CellState::Alive,
+            CellState::Alive => CellState::Dead,
+        }
     }
 }
 
-#[derive(Debug)]
+/// The kinds of live cells, used for per-kind censuses like [`Universe::count_by_state`].
+///
+/// Two-state rules like Conway's only have [`CellKind::Alive`], multi-state rules will add their own kinds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CellKind {
+    Alive,
+}
+
+/// An axis-aligned rectangle, all edges are inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Bounds {
     pub top: i32,
     pub right: i32,
@@ -25,6 +70,65 @@ pub struct Bounds {
     pub left: i32,
 }
 impl Bounds {
+    /// Bounds that contain nothing, returned by [`Universe::bounds`] for an empty universe.
+    ///
+    /// The edges are inverted so that growing the bounds to fit a position works without special cases.
+    pub fn empty() -> Self {
+        Self {
+            top: -i32::MAX,
+            right: -i32::MAX,
+            bottom: i32::MAX,
+            left: i32::MAX,
+        }
+    }
+    /// Whether the bounds contain no positions at all
+    pub fn is_empty(&self) -> bool {
+        self.left > self.right || self.bottom > self.top
+    }
+    /// The number of cells in the bounds, 0 if they're empty
+    pub fn area(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (self.right as i64 - self.left as i64 + 1) as u64
+            * (self.top as i64 - self.bottom as i64 + 1) as u64
+    }
+    /// The smallest bounds that contain both `self` and `other`
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Bounds {
+            top: self.top.max(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.min(other.bottom),
+            left: self.left.min(other.left),
+        }
+    }
+    /// The area covered by both `self` and `other`, `None` if they don't overlap
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let intersection = Bounds {
+            top: self.top.min(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.max(other.bottom),
+            left: self.left.max(other.left),
+        };
+        if intersection.is_empty() {
+            None
+        } else {
+            Some(intersection)
+        }
+    }
+    /// Grows the bounds to contain `pos`.
+    pub(crate) fn include(&mut self, pos: Position) {
+        self.top = self.top.max(pos.y);
+        self.bottom = self.bottom.min(pos.y);
+        self.left = self.left.min(pos.x);
+        self.right = self.right.max(pos.x);
+    }
     pub fn with_padding(&self, padding: i32) -> Self {
         Self {
             top: self.top + padding,
@@ -33,17 +137,55 @@ impl Bounds {
             left: self.left - padding,
         }
     }
+    /// Whether `pos` is inside the bounds or on their edge
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.x >= self.left && pos.x <= self.right && pos.y >= self.bottom && pos.y <= self.top
+    }
     pub fn size(&self) -> SizeInt {
         SizeInt::new(
             (self.left - self.right).abs(),
             (self.top - self.bottom).abs(),
         )
     }
+    /// The number of cells the bounds span horizontally divided by the number they span vertically.
+    ///
+    /// Empty bounds have a ratio of 1, as if they were square.
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.is_empty() {
+            return 1.0;
+        }
+        (self.right as i64 - self.left as i64 + 1) as f32
+            / (self.top as i64 - self.bottom as i64 + 1) as f32
+    }
+    /// The size of a cell and the position of the bottom left corner of the bounds
+    /// that fit every cell into `viewport` without stretching, centered along the shorter axis.
+    ///
+    /// Empty bounds get a scale of 1 with no offset.
+    pub fn fit_into(&self, viewport: SizeFloat) -> (f32, Vec2) {
+        if self.is_empty() {
+            return (1.0, Vec2::ZERO);
+        }
+        let width = (self.right - self.left + 1) as f32;
+        let height = (self.top - self.bottom + 1) as f32;
+        let scale = (viewport.width / width).min(viewport.height / height);
+        let offset = Vec2::new(
+            (viewport.width - width * scale) / 2.0,
+            (viewport.height - height * scale) / 2.0,
+        );
+        (scale, offset)
+    }
+}
+impl Default for Bounds {
+    fn default() -> Self {
+        Self::empty()
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct Materials {
     pub cell_alive: Handle<ColorMaterial>,
+    /// The material of dead cells drawn on top of an alive background, see [`Universe::background`]
+    pub cell_dead: Handle<ColorMaterial>,
 }
 
 /// A `HashMap` containing the positions and entities of all living cells
@@ -51,162 +193,3118 @@ pub type Cells = HashMap<Position, Cell>;
 
 #[derive(Clone, Default)]
 pub struct Universe {
+    /// The live cells, or while the [background](Universe::background) is alive, the cells that differ from it
     pub cells: Cells,
     pub materials: Materials,
+    /// Entities of cells that died without a game engine, despawned by [`Universe::sync_entities`]
+    stale_entities: Vec<Entity>,
+    /// Hidden entities of dead cells, reused for births instead of spawning new ones
+    entity_pool: Vec<Entity>,
+    /// The changes of the most recent ticks, oldest first, used for stepping back
+    history: VecDeque<TickDelta>,
+    /// The bounds of the live cells, kept up to date as cells are added and removed
+    bounds: Bounds,
+    /// Whether a cell on the edge of `bounds` was removed, so they have to be rescanned
+    bounds_stale: bool,
+    /// How many ticks the universe has been through, see [`Universe::generation`]
+    generation: u64,
+    /// Positions that are never alive, see [`Universe::add_walls`]
+    walls: HashSet<Position>,
+    /// Live cells that never die, see [`Universe::freeze`]
+    frozen: HashSet<Position>,
+    /// How many more generations recently dead positions count as decaying, see [`Universe::dead_decay`]
+    decay: HashMap<Position, u8>,
+    /// The [`SimulationConfig::dead_decay_generations`] of the latest tick, for rebuilding `decay` when stepping back
+    decay_generations: u8,
+    /// User-defined labels of live cells, see [`Universe::set_tag`]. Kept out of [`Cell`] so that untagged universes don't pay for them
+    tags: HashMap<Position, u32>,
+    /// The rectangle whose cells are alive unless they're in `cells`, see [`Universe::background`]
+    background: Option<Bounds>,
+    /// Whether the background flipped since the materials of the entities were last chosen
+    materials_stale: bool,
 }
 impl Universe {
     pub fn new(cells: Cells, materials: Materials) -> Self {
-        Self { cells, materials }
+        Self {
+            bounds: scan_bounds(&cells),
+            cells,
+            materials,
+            stale_entities: vec![],
+            entity_pool: vec![],
+            history: VecDeque::new(),
+            bounds_stale: false,
+            generation: 0,
+            walls: HashSet::new(),
+            frozen: HashSet::new(),
+            decay: HashMap::new(),
+            decay_generations: 0,
+            tags: HashMap::new(),
+            background: None,
+            materials_stale: false,
+        }
+    }
+    /// Creates an empty universe with room for at least `capacity` live cells before reallocating.
+    pub fn with_capacity(capacity: usize, materials: Materials) -> Self {
+        Self::new(Cells::with_capacity(capacity), materials)
     }
+    /// The bounds of the live cells.
+    ///
+    /// These are cached and updated as cells are added and removed, only rescanning every cell when a cell on the edge is removed.
+    /// Call [`Universe::recompute_bounds`] after modifying [`Universe::cells`] directly.
+    /// While the [background](Universe::background) is alive, they cover its whole rectangle.
     pub fn bounds(&self) -> Bounds {
-        let mut bounds = Bounds {
-            top: -MAX,
-            bottom: MAX,
-            left: MAX,
-            right: -MAX,
+        match self.background {
+            Some(region) => self.bounds.union(&region),
+            None => self.bounds,
+        }
+    }
+    /// The rectangle whose cells are all alive apart from the ones in [`Universe::cells`], `None` while the background is dead.
+    ///
+    /// Rules with `B0` bring every dead cell with no live neighbors to life, so in a finite topology the whole background comes alive.
+    /// Instead of filling [`Universe::cells`] with it, the background flips and the cells keep following a complemented rule,
+    /// the usual emulation of `B0` rules. A tick only has to look at the cells that differ from the background, however big the topology is.
+    ///
+    /// The rectangle is the topology's bounds at the tick the background came alive. It's kept until the background dies again,
+    /// so change the topology of a universe with an alive background only after [`Universe::clear_background`].
+    pub fn background(&self) -> Option<Bounds> {
+        self.background
+    }
+    /// Turns an alive background back into ordinary live cells, which takes a cell for every position of its rectangle.
+    pub fn clear_background(&mut self) {
+        let region = match self.background.take() {
+            Some(region) => region,
+            None => return,
         };
-        for (pos, _) in &self.cells {
-            if pos.y > bounds.top {
-                bounds.top = pos.y;
+        for pos in region_positions(region) {
+            if self.cells.contains_key(&pos) {
+                if let Some(Cell {
+                    entity: Some(entity),
+                    ..
+                }) = self.remove_cell(pos)
+                {
+                    self.stale_entities.push(entity);
+                }
+            } else if !self.is_wall(pos) {
+                self.insert_cell(pos, Cell::default());
             }
-            if pos.y < bounds.bottom {
-                bounds.bottom = pos.y;
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Whether `pos` is in the rectangle of an alive background
+    fn in_background(&self, pos: Position) -> bool {
+        matches!(self.background, Some(region) if region.contains(pos))
+    }
+    /// The positions of every live cell, in no particular order.
+    ///
+    /// These are the keys of [`Universe::cells`], unless the [background](Universe::background) is alive and its whole rectangle has to be walked.
+    pub fn live_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        let background = self.background;
+        let differing = self
+            .cells
+            .keys()
+            .copied()
+            .filter(move |pos| !matches!(background, Some(region) if region.contains(*pos)));
+        let filled = background
+            .into_iter()
+            .flat_map(region_positions)
+            .filter(move |pos| !self.cells.contains_key(pos) && !self.walls.contains(pos));
+        differing.chain(filled)
+    }
+    /// Rescans every live cell to update the cached [`Universe::bounds`].
+    pub fn recompute_bounds(&mut self) {
+        self.bounds = scan_bounds(&self.cells);
+        self.bounds_stale = false;
+    }
+    /// Flips the cells at `positions` between alive and dead, spawning and despawning entities as needed.
+    ///
+    /// Walls can't be brought to life, so toggling them does nothing.
+    pub fn toggle_cells_at(&mut self, commands: &mut Commands, positions: Vec<Position>) {
+        for pos in positions.iter().cloned() {
+            let alive = self.state_at(pos).toggle() == CellState::Alive;
+            self.set_alive(commands, pos, alive);
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Toggles the cells at `positions` like [`Universe::toggle_cells_at`], but without spawning or despawning any entities.
+    ///
+    /// Universes that are rendered should call [`Universe::sync_entities`] afterwards.
+    pub fn toggle_positions(&mut self, positions: impl IntoIterator<Item = Position>) {
+        for pos in positions {
+            if self.is_wall(pos) {
+                continue;
             }
-            if pos.x < bounds.left {
-                bounds.left = pos.x;
+            // Whatever the background is, a cell flips by joining or leaving the cells that differ from it
+            match self.remove_cell(pos) {
+                Some(Cell {
+                    entity: Some(entity),
+                    ..
+                }) => self.stale_entities.push(entity),
+                Some(_) => {}
+                None => self.insert_cell(pos, Cell::default()),
             }
-            if pos.x > bounds.right {
-                bounds.right = pos.x;
+            if self.state_at(pos) == CellState::Dead {
+                self.frozen.remove(&pos);
             }
         }
-        bounds
+        self.refresh_bounds();
+        self.forget_history();
     }
-    pub fn toggle_cells_at(&mut self, commands: &mut Commands, positions: Vec<Position>) {
-        for pos in positions.iter().cloned() {
-            let cell = &mut self.cells.get(&pos);
-            match cell {
-                Some(data) => {
-                    self.despawn_cell_entity(commands, data.entity);
-                    self.cells.remove(&pos);
-                }
-                None => {
-                    self.cells
-                        .insert(pos, Cell::new(self.spawn_cell_entity(commands, pos)));
-                }
+    /// Sets every cell in `bounds` alive or dead, spawning and despawning entities as needed.
+    ///
+    /// Cells that are already in the requested state are left untouched, and walls stay dead.
+    pub fn fill_rect(&mut self, commands: &mut Commands, bounds: Bounds, alive: bool) {
+        for pos in region_positions(bounds) {
+            self.set_alive(commands, pos, alive);
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Stores or removes the cell at `pos` so that it ends up alive or dead against the background, skipping walls.
+    ///
+    /// Call [`Universe::refresh_bounds`] once done editing cells.
+    fn set_alive(&mut self, commands: &mut Commands, pos: Position, alive: bool) {
+        if self.is_wall(pos) {
+            return;
+        }
+        if alive != self.in_background(pos) {
+            if !self.cells.contains_key(&pos) {
+                let entity = self.spawn_cell_entity(commands, pos);
+                self.insert_cell(pos, Cell::new(entity));
+            }
+        } else if let Some(cell) = self.remove_cell(pos) {
+            self.despawn_cell_entity(commands, cell.entity);
+        }
+        if !alive {
+            self.frozen.remove(&pos);
+        }
+    }
+    /// Brings dead cells in `bounds` to life with a `density` chance each, leaving live cells and walls alone.
+    ///
+    /// Only dead cells that aren't walls are rolled, row by row from the bottom left, so the same `rng` state and universe always give the same result.
+    pub fn sprinkle(
+        &mut self,
+        commands: &mut Commands,
+        bounds: Bounds,
+        density: f32,
+        rng: &mut impl Rng,
+    ) {
+        for pos in region_positions(bounds) {
+            if self.state_at(pos) == CellState::Dead
+                && !self.is_wall(pos)
+                && rng.gen::<f32>() < density
+            {
+                self.set_alive(commands, pos, true);
+            }
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Kills every cell in `bounds`, see [`Universe::fill_rect`].
+    pub fn clear_rect(&mut self, commands: &mut Commands, bounds: Bounds) {
+        self.fill_rect(commands, bounds, false);
+    }
+    /// Flips every cell in `bounds` between alive and dead, spawning and despawning entities as needed.
+    ///
+    /// Replaces the region with its complement, so inverting the same region twice restores it.
+    pub fn invert(&mut self, commands: &mut Commands, bounds: Bounds) {
+        self.toggle_cells_at(commands, region_positions(bounds).collect());
+    }
+    /// Kills every cell outside `bounds`, the complement of [`Universe::clear_rect`].
+    ///
+    /// Cells on the edge of `bounds` are kept.
+    pub fn trim(&mut self, commands: &mut Commands, bounds: Bounds) {
+        let outside: Vec<Position> = self
+            .live_positions()
+            .filter(|pos| !bounds.contains(*pos))
+            .collect();
+        for pos in outside {
+            self.set_alive(commands, pos, false);
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Turns `positions` into permanent walls, killing any live cells on them.
+    ///
+    /// Walls are never born and never change state. Whether they count as live neighbors is set by [`SimulationConfig::walls_count_as_alive`].
+    pub fn add_walls(
+        &mut self,
+        commands: &mut Commands,
+        positions: impl IntoIterator<Item = Position>,
+    ) {
+        for pos in positions {
+            if let Some(cell) = self.remove_cell(pos) {
+                self.despawn_cell_entity(commands, cell.entity);
+            }
+            self.frozen.remove(&pos);
+            self.walls.insert(pos);
+        }
+        self.refresh_bounds();
+        self.forget_history();
+    }
+    /// Turns walls at `positions` back into ordinary dead cells.
+    pub fn remove_walls(&mut self, positions: impl IntoIterator<Item = Position>) {
+        for pos in positions {
+            self.walls.remove(&pos);
+        }
+    }
+    pub fn walls(&self) -> &HashSet<Position> {
+        &self.walls
+    }
+    pub fn is_wall(&self, pos: Position) -> bool {
+        self.walls.contains(&pos)
+    }
+    /// Freezes the live cells at `positions` so that they never die, dead positions are skipped.
+    ///
+    /// Frozen cells still count as live neighbors, so the cells around them keep evolving normally.
+    /// A frozen cell only goes away when it's removed by an edit, like [`Universe::clear`].
+    pub fn freeze(&mut self, positions: impl IntoIterator<Item = Position>) {
+        for pos in positions {
+            if self.state_at(pos) == CellState::Alive {
+                self.frozen.insert(pos);
             }
         }
     }
+    /// Lets the cells at `positions` follow the rules again.
+    pub fn unfreeze(&mut self, positions: impl IntoIterator<Item = Position>) {
+        for pos in positions {
+            self.frozen.remove(&pos);
+        }
+    }
+    pub fn frozen(&self) -> &HashSet<Position> {
+        &self.frozen
+    }
+    pub fn is_frozen(&self, pos: Position) -> bool {
+        self.frozen.contains(&pos)
+    }
+    /// Adds a live cell, growing the cached bounds to fit it and ending the decay of the dead cell it replaces.
+    ///
+    /// The entity of a cell that was already at `pos` is despawned by the next [`Universe::sync_entities`].
+    /// Walls are never alive, so callers skip them before spawning an entity.
+    fn insert_cell(&mut self, pos: Position, cell: Cell) {
+        debug_assert!(!self.is_wall(pos), "walls are never alive");
+        self.decay.remove(&pos);
+        self.remove_tag(pos);
+        if let Some(Cell {
+            entity: Some(entity),
+            ..
+        }) = self.cells.insert(pos, cell)
+        {
+            self.stale_entities.push(entity);
+        }
+        self.bounds.include(pos);
+    }
+    /// Removes a live cell, marking the cached bounds for a rescan if it was on their edge.
+    ///
+    /// Call [`Universe::refresh_bounds`] once done removing cells. Frozen cells are only unfrozen by the edits that kill them,
+    /// as against an alive background a removed cell comes alive.
+    fn remove_cell(&mut self, pos: Position) -> Option<Cell> {
+        let cell = self.cells.remove(&pos)?;
+        self.remove_tag(pos);
+        let bounds = self.bounds;
+        if pos.x == bounds.left
+            || pos.x == bounds.right
+            || pos.y == bounds.bottom
+            || pos.y == bounds.top
+        {
+            self.bounds_stale = true;
+        }
+        Some(cell)
+    }
+    /// Drops the tag of a cell that died or was replaced, without hashing `pos` when no tags are in use.
+    fn remove_tag(&mut self, pos: Position) {
+        if !self.tags.is_empty() {
+            self.tags.remove(&pos);
+        }
+    }
+    /// Rescans the bounds if a cell on their edge was removed.
+    fn refresh_bounds(&mut self) {
+        if self.bounds_stale {
+            self.recompute_bounds();
+        }
+    }
     fn spawn_cell_entity(&self, commands: &mut Commands, pos: Position) -> Entity {
         let entity = commands.spawn().id();
         commands
             .entity(entity)
             .insert(Cell::new(entity))
             .insert_bundle(SpriteBundle {
-                material: self.materials.cell_alive.clone(),
+                material: self.material_at(pos),
                 ..Default::default()
             })
             .insert(pos)
             .insert(SizeFloat::new(1.0, 1.0));
         entity
     }
-    fn despawn_cell_entity(&self, commands: &mut Commands, entity: Entity) {
-        commands.entity(entity).despawn_recursive();
+    fn despawn_cell_entity(&self, commands: &mut Commands, entity: Option<Entity>) {
+        if let Some(entity) = entity {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    /// Hides the entity of a dead cell and keeps it for [`Universe::reuse_cell_entity`].
+    fn pool_cell_entity(&mut self, commands: &mut Commands, entity: Option<Entity>) {
+        if let Some(entity) = entity {
+            commands.entity(entity).remove::<Cell>().insert(Visible {
+                is_visible: false,
+                is_transparent: true,
+            });
+            self.entity_pool.push(entity);
+        }
+    }
+    /// Moves a pooled entity to `pos` and shows it again, spawning a new entity if the pool is empty.
+    fn reuse_cell_entity(&mut self, commands: &mut Commands, pos: Position) -> Entity {
+        let entity = match self.entity_pool.pop() {
+            Some(entity) => entity,
+            None => return self.spawn_cell_entity(commands, pos),
+        };
+        commands
+            .entity(entity)
+            .insert(Cell::new(entity))
+            .insert(self.material_at(pos))
+            .insert(Visible {
+                is_visible: true,
+                is_transparent: true,
+            })
+            .insert(pos);
+        entity
+    }
+    /// The material of a cell in [`Universe::cells`] at `pos`, which is dead if it differs from an alive background
+    fn material_at(&self, pos: Position) -> Handle<ColorMaterial> {
+        if self.in_background(pos) {
+            self.materials.cell_dead.clone()
+        } else {
+            self.materials.cell_alive.clone()
+        }
+    }
+    /// Gives every entity the material of its cell again after the background flipped
+    fn refresh_materials(&mut self, commands: &mut Commands) {
+        if !std::mem::take(&mut self.materials_stale) {
+            return;
+        }
+        for (pos, cell) in &self.cells {
+            if let Some(entity) = cell.entity {
+                commands.entity(entity).insert(self.material_at(*pos));
+            }
+        }
+    }
+    /// Despawns the hidden entities kept for reuse by ticks, for example before despawning the universe itself.
+    pub fn clear_entity_pool(&mut self, commands: &mut Commands) {
+        for entity in std::mem::take(&mut self.entity_pool) {
+            self.despawn_cell_entity(commands, Some(entity));
+        }
     }
+    /// Randomly generates a universe, seeding the random number generator from entropy.
     pub fn generate(
         commands: &mut Commands,
         materials: Materials,
         size: SizeInt,
         life_chance: f32,
-    ) -> Self {
-        let mut cells: Cells = HashMap::new();
-        let half_size = SizeInt::new(
-            (size.width as f32 / 2.0) as i32,
-            (size.height as f32 / 2.0) as i32,
-        );
-        for y in -half_size.height..half_size.height {
-            for x in -half_size.width..half_size.width {
-                let lives = random::<f32>() < life_chance;
-                if lives {
-                    cells.insert(Position::new(x, y), Cell::new(commands.spawn().id()));
+    ) -> Result<Self, InvalidSize> {
+        Self::generate_with_rng(
+            commands,
+            materials,
+            size,
+            life_chance,
+            &mut StdRng::from_entropy(),
+        )
+    }
+    /// Randomly generates a universe that is reproducible from the given `seed`.
+    pub fn generate_seeded(
+        commands: &mut Commands,
+        materials: Materials,
+        size: SizeInt,
+        life_chance: f32,
+        seed: u64,
+    ) -> Result<Self, InvalidSize> {
+        Self::generate_with_rng(
+            commands,
+            materials,
+            size,
+            life_chance,
+            &mut StdRng::seed_from_u64(seed),
+        )
+    }
+    /// Randomly generates a universe, drawing every random decision from `rng`.
+    ///
+    /// Cells are rolled row by row from the bottom left, so the same `rng` state always produces the same universe.
+    /// Fails without allocating anything if `size` is rejected by [`SizeInt::try_new`].
+    pub fn generate_with_rng(
+        commands: &mut Commands,
+        materials: Materials,
+        size: SizeInt,
+        life_chance: f32,
+        rng: &mut impl Rng,
+    ) -> Result<Self, InvalidSize> {
+        let size = SizeInt::try_new(size.width, size.height)?;
+        let positions = random_positions(size, life_chance, rng);
+        let mut universe = Self::with_capacity(positions.len(), materials);
+        for pos in positions {
+            universe.insert_cell(pos, Cell::new(commands.spawn().id()));
+        }
+        Ok(universe)
+    }
+    /// Generates a universe with exactly `count` live cells at distinct random positions in `bounds`.
+    ///
+    /// Unlike rolling every cell with a life chance, the population is always the same, which keeps densities equal between experiments.
+    /// Fails if `bounds` has fewer than `count` cells.
+    pub fn generate_count(
+        commands: &mut Commands,
+        materials: Materials,
+        bounds: Bounds,
+        count: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Self, TooManyCells> {
+        let (width, height) = if bounds.is_empty() {
+            (0, 0)
+        } else {
+            (
+                (bounds.right - bounds.left + 1) as usize,
+                (bounds.top - bounds.bottom + 1) as usize,
+            )
+        };
+        let available = width * height;
+        if count > available {
+            return Err(TooManyCells {
+                requested: count,
+                available,
+            });
+        }
+        let mut universe = Self::with_capacity(count, materials);
+        for index in rand::seq::index::sample(rng, available, count).into_iter() {
+            let pos = Position::new(
+                bounds.left + (index % width) as i32,
+                bounds.bottom + (index / width) as i32,
+            );
+            let entity = universe.spawn_cell_entity(commands, pos);
+            universe.insert_cell(pos, Cell::new(entity));
+        }
+        Ok(universe)
+    }
+    /// Randomly generates a universe without spawning any entities, drawing every random decision from `rng`.
+    ///
+    /// Produces the same cells as [`Universe::generate_with_rng`] for the same `rng` state.
+    /// Unlike it, `size` isn't checked, so sizes from user input should go through [`SizeInt::try_new`] or [`SimulationConfig::validate`] first.
+    pub fn random(size: SizeInt, life_chance: f32, rng: &mut impl Rng) -> Self {
+        Self::from_positions(random_positions(size, life_chance, rng))
+    }
+    /// Creates a universe from a pattern without spawning any entities.
+    ///
+    /// `generation` sets the starting [`Universe::generation`], for patterns saved at a later phase or resumed sessions. Defaults to 0.
+    pub fn from_pattern(pattern: &CellPattern, generation: Option<u64>) -> Self {
+        let mut universe = Self::from_positions(pattern.cells.iter().copied());
+        universe.generation = generation.unwrap_or(0);
+        universe
+    }
+    /// Copies the live cells into a pattern, moved so that the bottom left corner is at the origin.
+    ///
+    /// See [`CellPattern::normalized`]. Entities, walls and history aren't part of the pattern.
+    pub fn to_pattern(&self) -> CellPattern {
+        CellPattern::new(self.live_positions().collect()).normalized()
+    }
+    /// Creates a universe with live cells at `positions` without spawning any entities.
+    pub fn from_positions(positions: impl IntoIterator<Item = Position>) -> Self {
+        let cells = positions
+            .into_iter()
+            .map(|pos| (pos, Cell::default()))
+            .collect();
+        Self::new(cells, Materials::default())
+    }
+    /// A copy of the live cells with their positions converted to the given coordinate convention, without any entities.
+    ///
+    /// The `_with` variants of the exporters, such as [`Universe::to_writer_with`], write this copy.
+    pub fn exported(&self, convention: CoordinateConvention) -> Universe {
+        Universe::from_positions(self.live_positions().map(|pos| convention.export(pos)))
+    }
+    /// The tag of the live cell at `pos`, `None` if the cell is dead or untagged
+    pub fn tag_at(&self, pos: Position) -> Option<u32> {
+        self.tags.get(&pos).copied()
+    }
+    /// Tags the live cell at `pos`, or clears its tag with `None`, returning `false` if the cell is dead.
+    ///
+    /// Tags follow a cell for as long as it survives, so tagging the cells of a structure keeps track of them as a group.
+    /// They're stored apart from the cells, so a universe without tags takes no extra memory.
+    pub fn set_tag(&mut self, pos: Position, tag: Option<u32>) -> bool {
+        if self.state_at(pos) == CellState::Dead {
+            return false;
+        }
+        match tag {
+            Some(tag) => self.tags.insert(pos, tag),
+            None => self.tags.remove(&pos),
+        };
+        true
+    }
+    /// Copies the live cells, their tags and the background into a new universe without entities or history
+    pub(crate) fn detached(&self) -> Universe {
+        let cells = self
+            .cells
+            .keys()
+            .map(|pos| (*pos, Cell::default()))
+            .collect();
+        let mut universe = Universe::new(cells, Materials::default());
+        universe.tags = self.tags.clone();
+        universe.background = self.background;
+        universe
+    }
+    /// Whether the cell at `pos` is alive, for code that doesn't care how cells are stored
+    pub fn state_at(&self, pos: Position) -> CellState {
+        let alive = match self.background {
+            None => self.cells.contains_key(&pos),
+            // Walls are dead even against an alive background
+            Some(region) => {
+                self.cells.contains_key(&pos) != region.contains(pos) && !self.walls.contains(&pos)
+            }
+        };
+        if alive {
+            CellState::Alive
+        } else {
+            CellState::Dead
+        }
+    }
+    /// How many ticks the universe has been through, counting from its starting generation
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+    /// Copies the states of the cells in [`Universe::bounds`] into a flat buffer, see [`Universe::write_state_buffer`].
+    pub fn state_slice(&self) -> (SizeInt, Vec<u8>) {
+        let mut buffer = vec![];
+        let size = self.write_state_buffer(self.bounds(), &mut buffer);
+        (size, buffer)
+    }
+    /// Writes the states of the cells in `bounds` into `buffer` as 1 for alive and 0 for dead, returning the width and height of the grid.
+    ///
+    /// The cells are in row-major order starting from the bottom left, so the cell at `pos` is at index
+    /// `(pos.y - bounds.bottom) * width + (pos.x - bounds.left)`. Reusing `buffer` between frames avoids allocating,
+    /// which suits renderers that upload the whole grid at once.
+    pub fn write_state_buffer(&self, bounds: Bounds, buffer: &mut Vec<u8>) -> SizeInt {
+        // Both edges are inside the bounds, unlike with `Bounds::size`
+        let size = if bounds.is_empty() {
+            SizeInt::new(0, 0)
+        } else {
+            SizeInt::new(
+                bounds.right - bounds.left + 1,
+                bounds.top - bounds.bottom + 1,
+            )
+        };
+        buffer.clear();
+        buffer.resize((size.width * size.height) as usize, 0);
+        if self.background.is_some() {
+            // Only the requested cells are looked up, however big the background is
+            for (state, pos) in buffer.iter_mut().zip(region_positions(bounds)) {
+                *state = (self.state_at(pos) == CellState::Alive) as u8;
+            }
+            return size;
+        }
+        for pos in self.cells.keys() {
+            if bounds.contains(*pos) {
+                let index = (pos.y - bounds.bottom) * size.width + (pos.x - bounds.left);
+                buffer[index as usize] = 1;
+            }
+        }
+        size
+    }
+    /// The number of live cells
+    pub fn population(&self) -> usize {
+        let region = match self.background {
+            Some(region) => region,
+            None => return self.cells.len(),
+        };
+        let inside = self
+            .cells
+            .keys()
+            .filter(|pos| region.contains(**pos))
+            .count();
+        let walls = self
+            .walls
+            .iter()
+            .filter(|pos| region.contains(**pos))
+            .count();
+        region.area() as usize - inside - walls + (self.cells.len() - inside)
+    }
+    /// The number of live cells inside `bounds`.
+    ///
+    /// Checks every position of the region or every live cell, whichever there are fewer of.
+    /// While the [background](Universe::background) is alive, every position of the region is checked.
+    pub fn count_in_region(&self, bounds: Bounds) -> usize {
+        if bounds.is_empty() {
+            return 0;
+        }
+        if self.background.is_some() || bounds.area() < self.cells.len() as u64 {
+            region_positions(bounds)
+                .filter(|pos| self.state_at(*pos) == CellState::Alive)
+                .count()
+        } else {
+            self.cells
+                .keys()
+                .filter(|pos| bounds.contains(**pos))
+                .count()
+        }
+    }
+    /// The fraction of the cells inside `bounds` that are alive, from 0.0 to 1.0.
+    ///
+    /// Empty bounds have no cells, so their density is 0.0.
+    pub fn density(&self, bounds: Bounds) -> f32 {
+        match bounds.area() {
+            0 => 0.0,
+            area => (self.count_in_region(bounds) as f64 / area as f64) as f32,
+        }
+    }
+    /// The number of live cells of each kind, kinds without any cells are left out
+    pub fn count_by_state(&self) -> HashMap<CellKind, usize> {
+        let mut counts = HashMap::new();
+        for pos in self.live_positions() {
+            // Cells of an alive background aren't stored, so they're ordinary live cells
+            let kind = self.cells.get(&pos).map_or(CellKind::Alive, Cell::kind);
+            *counts.entry(kind).or_insert(0) += 1;
+        }
+        counts
+    }
+    /// How many live cells have each number of live neighbors, indexed by the neighbor count.
+    ///
+    /// Shows at a glance whether a universe is crowded or sparse, which helps when tuning custom rules.
+    pub fn neighbor_count_histogram(&self) -> [usize; 9] {
+        let mut histogram = [0; 9];
+        for pos in self.live_positions() {
+            histogram[self.live_neighbor_count(pos) as usize] += 1;
+        }
+        histogram
+    }
+    /// The live cells with at least one dead neighbor among their 8 surrounding cells, which form the perimeter of every blob
+    pub fn outline(&self) -> HashSet<Position> {
+        self.live_positions()
+            .filter(|pos| self.live_neighbor_count(*pos) < 8)
+            .collect()
+    }
+    /// Groups the live cells into clusters of cells connected through any of their 8 surrounding cells.
+    ///
+    /// Every cluster is sorted, and the clusters are sorted by their first cell, so the same cells always give the same clusters in the same order.
+    pub fn clusters(&self) -> Vec<Vec<Position>> {
+        let mut unvisited: HashSet<Position> = self.live_positions().collect();
+        let mut clusters = vec![];
+        while let Some(&start) = unvisited.iter().next() {
+            unvisited.remove(&start);
+            let mut cluster = vec![start];
+            let mut frontier = vec![start];
+            while let Some(pos) = frontier.pop() {
+                for neighbor in pos.neighbors() {
+                    if unvisited.remove(&neighbor) {
+                        cluster.push(neighbor);
+                        frontier.push(neighbor);
+                    }
                 }
             }
+            cluster.sort();
+            clusters.push(cluster);
         }
-        Self::new(cells, materials)
+        clusters.sort();
+        clusters
     }
     pub fn live_neighbor_count(&self, pos: Position) -> u8 {
-        let mut count = 0;
-        for neighbor_pos in pos.neighbors() {
-            if self.cells.get(&neighbor_pos).is_some() {
-                count += 1;
+        NEIGHBOR_OFFSETS
+            .iter()
+            .filter(|(dx, dy)| {
+                self.state_at(Position::new(pos.x + dx, pos.y + dy)) == CellState::Alive
+            })
+            .count() as u8
+    }
+    /// Counts the live neighbors of `pos` within the given topology.
+    ///
+    /// Neighbors outside of a finite topology are treated according to its [`OutOfBoundsPolicy`].
+    pub fn live_neighbor_count_in(&self, pos: Position, topology: &Topology) -> u8 {
+        self.live_neighbor_count_with(pos, topology, Neighborhood::Moore)
+    }
+    /// Like [`Universe::live_neighbor_count_in`], but for the given neighborhood
+    pub fn live_neighbor_count_with(
+        &self,
+        pos: Position,
+        topology: &Topology,
+        neighborhood: Neighborhood,
+    ) -> u8 {
+        count_neighbors(pos, topology, neighborhood, |pos| {
+            self.state_at(pos) == CellState::Alive
+        })
+    }
+    /// Sums the weights of the live neighbors of `pos` within the given topology, see [`NeighborWeights`].
+    pub fn weighted_neighbor_sum(
+        &self,
+        pos: Position,
+        topology: &Topology,
+        weights: &NeighborWeights,
+    ) -> i32 {
+        sum_neighbor_weights(pos, topology, weights, |pos| {
+            self.state_at(pos) == CellState::Alive
+        })
+    }
+    /// Counts the neighbors of `pos` in [`Universe::cells`] the way [`Universe::tick`] does, including walls if they count as alive.
+    ///
+    /// Against an alive background, `config` is the complemented one of [`Universe::inversion`].
+    fn live_neighbor_count_for(&self, pos: Position, config: &SimulationConfig) -> u8 {
+        let stored = |pos: Position| self.cells.contains_key(&pos);
+        if let Some(weights) = &config.neighbor_weights {
+            let mut sum = sum_neighbor_weights(pos, &config.topology, weights, stored);
+            if config.walls_count_as_alive {
+                for (offset, weight) in &weights.weights {
+                    let wall = match config
+                        .topology
+                        .resolve(Position::new(pos.x + offset.x, pos.y + offset.y))
+                    {
+                        NeighborLookup::At(resolved) => self.walls.contains(&resolved),
+                        NeighborLookup::Alive | NeighborLookup::Dead => false,
+                    };
+                    if wall {
+                        sum += weight;
+                    }
+                }
             }
+            return sum.clamp(0, u8::MAX as i32) as u8;
         }
-        count
+        let count = count_neighbors(pos, &config.topology, config.neighborhood, stored);
+        if !config.walls_count_as_alive || self.walls.is_empty() {
+            return count;
+        }
+        let walls = config
+            .neighborhood
+            .offsets()
+            .iter()
+            .filter(|(dx, dy)| {
+                match config
+                    .topology
+                    .resolve(Position::new(pos.x + dx, pos.y + dy))
+                {
+                    NeighborLookup::At(resolved) => self.walls.contains(&resolved),
+                    NeighborLookup::Alive | NeighborLookup::Dead => false,
+                }
+            })
+            .count();
+        count + walls as u8
+    }
+    /// The 8 neighbors of `pos` that are inside `bounds`, for simulating or searching a clipped region.
+    pub fn neighbors_within(pos: Position, bounds: &Bounds) -> Vec<Position> {
+        pos.neighbors()
+            .into_iter()
+            .filter(|neighbor_pos| bounds.contains(*neighbor_pos))
+            .collect()
     }
-    /// Plays one frame of the simulation.
+    /// Computes the [Chebyshev distance](https://en.wikipedia.org/wiki/Chebyshev_distance) from every dead cell in `bounds` to the nearest live cell.
     ///
-    /// ## Arguments
+    /// Dead cells further than `max_dist` away from any live cell are left out. Live cells just outside of `bounds` are taken into account.
+    pub fn distance_field(&self, bounds: Bounds, max_dist: u32) -> HashMap<Position, u32> {
+        // Any live cell close enough to matter is within `max_dist` of the bounds
+        let search_bounds = bounds.with_padding(max_dist as i32);
+        let mut distances: HashMap<Position, u32> = HashMap::new();
+        let mut queue: VecDeque<Position> = VecDeque::new();
+        for pos in self.live_positions() {
+            if search_bounds.contains(pos) {
+                distances.insert(pos, 0);
+                queue.push_back(pos);
+            }
+        }
+
+        // Breadth-first search from all live cells at once, stepping to all 8 neighbors
+        while let Some(pos) = queue.pop_front() {
+            let dist = distances[&pos];
+            if dist >= max_dist {
+                continue;
+            }
+            for neighbor_pos in Universe::neighbors_within(pos, &search_bounds) {
+                if let Entry::Vacant(entry) = distances.entry(neighbor_pos) {
+                    entry.insert(dist + 1);
+                    queue.push_back(neighbor_pos);
+                }
+            }
+        }
+
+        distances
+            .into_iter()
+            .filter(|(pos, dist)| *dist > 0 && bounds.contains(*pos))
+            .collect()
+    }
+    /// Plays one frame of the simulation using the rules and topology of `config`, returning the births and deaths it caused.
+    pub fn tick(&mut self, commands: &mut Commands, config: &SimulationConfig) -> TickDelta {
+        let delta = self.tick_delta(config);
+        self.apply_delta(commands, &delta);
+        self.record_tick(&delta, config);
+        self.generation += 1;
+        delta
+    }
+    /// Plays one frame like [`Universe::tick`], but only re-evaluates the `dirty` cells and the cells around them.
     ///
-    /// - `allowed_neighbors` - How many neighbors a cell can live with
-    /// - `allowed_neighbors_for_birth` - How many neighbors are required for a dead cell to become a live cell, as if by reproduction
-    pub fn tick(
+    /// Everything else is trusted not to change, which holds right after a localized edit of a board that was otherwise still,
+    /// such as a huge board of still lifes where only the toggled cells are dirty.
+    /// If any cell outside of `dirty` would have changed in a full tick, the result is wrong.
+    pub fn tick_dirty(
         &mut self,
         commands: &mut Commands,
-        allowed_neighbors: &Vec<u8>,
-        allowed_neighbors_for_birth: &Vec<u8>,
-    ) {
-        let mut next: Cells = self.cells.clone();
-        let mut visited: Vec<Position> = vec![];
-        for (pos, cell) in self.cells.iter() {
-            if visited.contains(&pos) {
+        dirty: &HashSet<Position>,
+        config: &SimulationConfig,
+    ) -> TickDelta {
+        let inversion = self.inversion(config);
+        let config = inversion
+            .as_ref()
+            .map_or(config, |inversion| &inversion.config);
+        let mut delta = TickDelta::default();
+        let mut visited = HashSet::new();
+        let candidates = dirty
+            .iter()
+            .flat_map(|pos| std::iter::once(*pos).chain(influenced_by(*pos, config)));
+        for pos in candidates {
+            if !self.cells.contains_key(&pos) {
+                if self.is_born(pos, config, &mut visited) {
+                    delta.births.push(pos);
+                }
+                continue;
+            }
+            if !visited.insert(pos) || self.frozen.contains(&pos) {
+                continue;
+            }
+            if !config.topology.contains(pos) {
+                delta.deaths.push((pos, DeathCause::OutOfBounds));
+                continue;
+            }
+            let live_neighbors = self.live_neighbor_count_for(pos, config);
+            if !config.survives(live_neighbors) {
+                let cause =
+                    DeathCause::from_neighbor_count(live_neighbors, &config.allowed_neighbors);
+                delta.deaths.push((pos, cause));
+            }
+        }
+        if let Some(inversion) = &inversion {
+            self.finish_inverted_delta(inversion, &mut delta);
+        }
+        delta.births.sort();
+        delta.deaths.sort_by_key(|(pos, _)| *pos);
+        self.apply_delta(commands, &delta);
+        self.record_tick(&delta, config);
+        self.generation += 1;
+        delta
+    }
+    /// Computes the births and deaths the next frame would cause without changing the universe.
+    ///
+    /// Both lists are sorted by position so that the result doesn't depend on `HashMap` ordering.
+    pub fn tick_delta(&self, config: &SimulationConfig) -> TickDelta {
+        let mut scratch = TickScratch::default();
+        self.tick_delta_into(config, &mut scratch);
+        scratch.delta
+    }
+    /// Computes the next frame like [`Universe::tick_delta`], reusing the buffers of `scratch`.
+    fn tick_delta_into(&self, config: &SimulationConfig, scratch: &mut TickScratch) {
+        match self.inversion(config) {
+            Some(inversion) => {
+                self.changes_into(&inversion.config, scratch);
+                self.finish_inverted_delta(&inversion, &mut scratch.delta);
+            }
+            None => self.changes_into(config, scratch),
+        }
+        scratch.delta.births.sort();
+        scratch.delta.deaths.sort_by_key(|(pos, _)| *pos);
+    }
+    /// Finds the cells of [`Universe::cells`] that change under `config`, only looking at the cells around them.
+    fn changes_into(&self, config: &SimulationConfig, scratch: &mut TickScratch) {
+        let topology = &config.topology;
+        let TickScratch { delta, visited } = scratch;
+        delta.births.clear();
+        delta.deaths.clear();
+        delta.flipped_background = None;
+        visited.clear();
+        for pos in self.cells.keys() {
+            // Cells that were placed outside of a bounded space can't live there.
+            if !topology.contains(*pos) && !self.frozen.contains(pos) {
+                delta.deaths.push((*pos, DeathCause::OutOfBounds));
                 continue;
             }
 
-            // Die if too many/not enough neighbors.
-            let live_neighbors = self.live_neighbor_count(pos.to_owned());
-            let dies = !allowed_neighbors.contains(&live_neighbors);
-            if dies {
-                self.despawn_cell_entity(commands, cell.entity);
-                next.remove(&pos);
+            // Die if too many/not enough neighbors, unless frozen.
+            let live_neighbors = self.live_neighbor_count_for(*pos, config);
+            if !config.survives(live_neighbors) && !self.frozen.contains(pos) {
+                let cause =
+                    DeathCause::from_neighbor_count(live_neighbors, &config.allowed_neighbors);
+                delta.deaths.push((*pos, cause));
             }
 
             // Loop through dead neighbors.
             // Neighbors become alive if they have the right amount of neighbors.
-            for neighbor_pos in pos.neighbors() {
-                if visited.contains(&neighbor_pos) || self.cells.get(&neighbor_pos).is_some() {
-                    continue;
+            for neighbor_pos in influenced_by(*pos, config) {
+                if self.is_born(neighbor_pos, config, visited) {
+                    delta.births.push(neighbor_pos);
                 }
-                let neighbor_cell = self.cells.get(&neighbor_pos);
-                let neighbor_live_neighbors = self.live_neighbor_count(neighbor_pos);
-                let is_born = neighbor_cell.is_none()
-                    && allowed_neighbors_for_birth.contains(&neighbor_live_neighbors);
-
-                if is_born {
-                    // Neighbor is born, insert into next generation and spawn entity
-                    next.insert(
-                        neighbor_pos,
-                        Cell::new(self.spawn_cell_entity(commands, neighbor_pos)),
-                    );
+            }
+        }
+        // Edge cells have live neighbors outside of the bounds even when no live cell is nearby
+        if let (Some(bounds), Some(OutOfBoundsPolicy::Alive)) =
+            (topology.bounds(), topology.out_of_bounds_policy())
+        {
+            for pos in edge_positions(bounds) {
+                if self.is_born(pos, config, visited) {
+                    delta.births.push(pos);
                 }
-                visited.push(neighbor_pos);
             }
-            visited.push(pos.to_owned());
         }
-        self.cells = next;
-    }
-}
-
-impl fmt::Display for Universe {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let bounds = self.bounds();
-        info!("{:?}", bounds);
-        for y in (bounds.bottom..bounds.top + 1).rev() {
-            write!(f, "\n")?;
-            for x in bounds.left..bounds.right + 1 {
-                let cell = self.cells.get(&Position::new(x, y));
-                let symbol = if cell.is_some() { '◼' } else { '◻' };
-                write!(f, "{}", symbol)?;
+        // Walls that count as alive can bring cells to life without any live cell nearby
+        if config.walls_count_as_alive {
+            for wall in &self.walls {
+                for pos in influenced_by(*wall, config) {
+                    if self.is_born(pos, config, visited) {
+                        delta.births.push(pos);
+                    }
+                }
+            }
+        }
+    }
+    /// The complemented rule that runs a tick of `config` against a flipped background, `None` if the background stays dead.
+    ///
+    /// With `B0` every cell far from any live cell is born, so the background of a finite topology comes alive.
+    /// Once it's alive, [`Universe::cells`] holds the cells that differ from it and neighbors are counted among them,
+    /// so the live neighbors of a cell are the full count minus the counted ones. Outside neighbors that are dead
+    /// differ from the background and walls differ unless they count as alive, which flips both.
+    /// Cells far from any stored cell follow the background, so the complemented rule never has `B0` of its own
+    /// and the sparse tick works unchanged.
+    fn inversion(&self, config: &SimulationConfig) -> Option<Inversion> {
+        let alive_before = self.background.is_some();
+        let region = match self.background {
+            Some(region) => region,
+            None if config.is_born(0) => *config.topology.bounds()?,
+            None => return None,
+        };
+        let full = config.full_neighbor_count()?;
+        let alive_after = if alive_before {
+            config.survives(full)
+        } else {
+            true
+        };
+        let stays_stored = |stored: bool, count: u8| {
+            let alive = stored != alive_before;
+            let live_neighbors = if alive_before { full - count } else { count };
+            let alive_next = if alive {
+                config.survives(live_neighbors)
+            } else {
+                config.is_born(live_neighbors)
+            };
+            alive_next != alive_after
+        };
+        let mut inverted = config.clone();
+        inverted.allowed_neighbors = (0..=full)
+            .filter(|count| stays_stored(true, *count))
+            .collect();
+        inverted.allowed_neighbors_for_birth = (0..=full)
+            .filter(|count| stays_stored(false, *count))
+            .collect();
+        if alive_before {
+            let policy = match config.topology.out_of_bounds_policy() {
+                Some(OutOfBoundsPolicy::Dead) | None => OutOfBoundsPolicy::Alive,
+                Some(OutOfBoundsPolicy::Alive) => OutOfBoundsPolicy::Dead,
+                Some(policy) => policy,
+            };
+            inverted.topology = Topology::Region(region, policy);
+            inverted.walls_count_as_alive = !config.walls_count_as_alive;
+        }
+        Some(Inversion {
+            config: inverted,
+            region,
+            alive_after,
+        })
+    }
+    /// Records whether the background flipped and keeps frozen cells alive, which the complemented rule knows nothing about.
+    fn finish_inverted_delta(&self, inversion: &Inversion, delta: &mut TickDelta) {
+        if inversion.alive_after != self.background.is_some() {
+            delta.flipped_background = Some(inversion.region);
+        }
+        if self.frozen.is_empty() {
+            return;
+        }
+        delta.births.retain(|pos| !self.frozen.contains(pos));
+        delta.deaths.retain(|(pos, _)| !self.frozen.contains(pos));
+        for pos in &self.frozen {
+            // Frozen cells are stored unless the background they're on is alive
+            let stored = !(inversion.alive_after && inversion.region.contains(*pos));
+            match (self.cells.contains_key(pos), stored) {
+                (false, true) => delta.births.push(*pos),
+                (true, false) => {
+                    let count = self.live_neighbor_count_for(*pos, &inversion.config);
+                    let cause =
+                        DeathCause::from_neighbor_count(count, &inversion.config.allowed_neighbors);
+                    delta.deaths.push((*pos, cause));
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Whether the dead cell at `pos` is born in the next frame, skipping cells that are alive or already in `visited`.
+    fn is_born(
+        &self,
+        pos: Position,
+        config: &SimulationConfig,
+        visited: &mut HashSet<Position>,
+    ) -> bool {
+        if self.cells.contains_key(&pos) || self.walls.contains(&pos) || !visited.insert(pos) {
+            return false;
+        }
+        let live_neighbors = self.live_neighbor_count_for(pos, config);
+        config.is_born(live_neighbors)
+    }
+    /// Plays one frame of the simulation like [`Universe::tick`], but without spawning or despawning any entities.
+    ///
+    /// Meant for universes that aren't rendered by a game engine, such as ones created with [`Universe::from_positions`].
+    /// Universes that are rendered should call [`Universe::sync_entities`] afterwards.
+    pub fn step(&mut self, config: &SimulationConfig) -> TickDelta {
+        let delta = self.tick_delta(config);
+        self.step_with_delta(delta, config)
+    }
+    /// Computes the next frame as a new universe without entities or history, leaving `self` unchanged.
+    ///
+    /// Keeping the previous state around makes diffing generations and comparing rules easy.
+    /// Walls and frozen cells are copied over, and the generation is one higher than `self`'s.
+    pub fn next_generation(&self, config: &SimulationConfig) -> Universe {
+        let delta = self.tick_delta(config);
+        let mut next = self.detached();
+        next.walls = self.walls.clone();
+        next.frozen = self.frozen.clone();
+        let tags = next.set_aside_tags(&delta);
+        for (pos, _) in &delta.deaths {
+            next.remove_cell(*pos);
+        }
+        for pos in &delta.births {
+            next.insert_cell(*pos, Cell::default());
+        }
+        next.finish_delta(&delta, tags);
+        next.generation = self.generation + 1;
+        next
+    }
+    /// Plays one frame like [`Universe::step`], but keeps all of its working memory in `scratch`.
+    ///
+    /// This is the low-level counterpart to the convenient [`Universe::tick`] and [`Universe::step`], which allocate fresh buffers every frame.
+    /// Reusing the same scratch across frames makes ticking allocation-free once the buffers have grown to fit the universe,
+    /// except for the copies kept for [`SimulationConfig::history_depth`].
+    pub fn tick_with_scratch<'a>(
+        &mut self,
+        scratch: &'a mut TickScratch,
+        config: &SimulationConfig,
+    ) -> &'a TickDelta {
+        self.tick_delta_into(config, scratch);
+        self.apply_step(&scratch.delta, config);
+        &scratch.delta
+    }
+    /// Applies an already computed `delta` like [`Universe::step`] does
+    pub(crate) fn step_with_delta(
+        &mut self,
+        delta: TickDelta,
+        config: &SimulationConfig,
+    ) -> TickDelta {
+        self.apply_step(&delta, config);
+        delta
+    }
+    fn apply_step(&mut self, delta: &TickDelta, config: &SimulationConfig) {
+        let tags = self.set_aside_tags(delta);
+        for (pos, _) in &delta.deaths {
+            if let Some(Cell {
+                entity: Some(entity),
+                ..
+            }) = self.remove_cell(*pos)
+            {
+                self.stale_entities.push(entity);
+            }
+        }
+        for pos in &delta.births {
+            self.insert_cell(*pos, Cell::default());
+        }
+        self.finish_delta(delta, tags);
+        self.record_tick(delta, config);
+        self.generation += 1;
+    }
+    /// Takes the tags out of the way of a delta against an alive background, whose births and deaths aren't those of the live cells
+    fn set_aside_tags(&mut self, delta: &TickDelta) -> Option<HashMap<Position, u32>> {
+        let flips = self.background.is_some() || delta.flipped_background.is_some();
+        (flips && !self.tags.is_empty()).then(|| std::mem::take(&mut self.tags))
+    }
+    /// Flips the background if `delta` says so and puts back the tags of the cells that survived
+    fn finish_delta(&mut self, delta: &TickDelta, tags: Option<HashMap<Position, u32>>) {
+        if let Some(region) = delta.flipped_background {
+            self.flip_background(region);
+        }
+        self.restore_tags(tags);
+        self.refresh_bounds();
+    }
+    /// Puts back the tags taken by [`Universe::set_aside_tags`] whose cells are still alive
+    fn restore_tags(&mut self, tags: Option<HashMap<Position, u32>>) {
+        if let Some(tags) = tags {
+            self.tags = tags
+                .into_iter()
+                .filter(|(pos, _)| self.state_at(*pos) == CellState::Alive)
+                .collect();
+        }
+    }
+    fn flip_background(&mut self, region: Bounds) {
+        self.background = match self.background {
+            Some(_) => None,
+            None => Some(region),
+        };
+        self.materials_stale = true;
+    }
+    /// Plays `generations` frames without spawning or despawning any entities, see [`Universe::step`].
+    ///
+    /// Useful for silently fast-forwarding a rendered universe, followed by a single [`Universe::sync_entities`].
+    pub fn advance(&mut self, generations: u32, config: &SimulationConfig) {
+        for _ in 0..generations {
+            self.step(config);
+        }
+    }
+    /// Undoes the most recent tick, returning `false` if there is no history left.
+    pub fn step_back(&mut self, commands: &mut Commands) -> bool {
+        self.step_back_n(commands, 1) == 1
+    }
+    /// Undoes up to `n` of the most recent ticks, returning how many were actually undone.
+    ///
+    /// Stops early when the history runs out, see [`SimulationConfig::history_depth`].
+    /// Entities are only updated once at the end, however many ticks are undone.
+    pub fn step_back_n(&mut self, commands: &mut Commands, n: usize) -> usize {
+        let mut undone = 0;
+        while undone < n {
+            let delta = match self.history.pop_back() {
+                Some(delta) => delta,
+                None => break,
+            };
+            for pos in &delta.births {
+                if let Some(Cell {
+                    entity: Some(entity),
+                    ..
+                }) = self.remove_cell(*pos)
+                {
+                    self.stale_entities.push(entity);
+                }
+            }
+            for (pos, _) in &delta.deaths {
+                self.insert_cell(*pos, Cell::default());
+            }
+            if let Some(region) = delta.flipped_background {
+                self.flip_background(region);
+            }
+            undone += 1;
+        }
+        // Cells frozen after the ticks that brought them to life are gone again
+        let frozen = std::mem::take(&mut self.frozen);
+        self.frozen = frozen
+            .into_iter()
+            .filter(|pos| self.state_at(*pos) == CellState::Alive)
+            .collect();
+        self.generation = self.generation.saturating_sub(undone as u64);
+        self.rebuild_decay();
+        self.refresh_bounds();
+        self.sync_entities(commands);
+        undone
+    }
+    /// How many ticks can currently be undone.
+    ///
+    /// Editing the cells by hand clears the history, since undoing ticks from before the edit would make up a board that never existed.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+    /// Drops the ticks that can be undone after the cells were edited by hand
+    fn forget_history(&mut self) {
+        self.history.clear();
+    }
+    /// How many more generations the dead cell at `pos` decays for, `None` if it's alive or has been dead for longer.
+    ///
+    /// A cell that just died starts at [`SimulationConfig::dead_decay_generations`] and counts down by one every tick,
+    /// which is handy for fading out dead cells. Stepping back restores the decay of the remaining generations,
+    /// exactly if [`SimulationConfig::history_depth`] is at least as long as the decay.
+    /// Decay isn't tracked while the [background](Universe::background) is alive, as the live cells aren't stored then.
+    pub fn dead_decay(&self, pos: Position) -> Option<u8> {
+        self.decay.get(&pos).copied()
+    }
+    /// Remembers `delta` for stepping back and updates the decay of dead cells
+    fn record_tick(&mut self, delta: &TickDelta, config: &SimulationConfig) {
+        self.decay_generations = config.dead_decay_generations;
+        if self.background.is_some() || delta.flipped_background.is_some() {
+            self.decay.clear();
+        } else {
+            update_decay(&mut self.decay, delta, self.decay_generations);
+        }
+        self.record_history(delta, config.history_depth);
+    }
+    /// Recomputes the decay from the deltas in the history, which are all that decay depends on
+    fn rebuild_decay(&mut self) {
+        self.decay.clear();
+        let recent = self
+            .history
+            .len()
+            .saturating_sub(self.decay_generations as usize);
+        for delta in self.history.range(recent..) {
+            if delta.flipped_background.is_some() {
+                self.decay.clear();
+            } else {
+                update_decay(&mut self.decay, delta, self.decay_generations);
+            }
+        }
+        if self.background.is_some() {
+            self.decay.clear();
+        }
+    }
+    /// Remembers `delta` for stepping back, forgetting the oldest ticks beyond `depth`
+    fn record_history(&mut self, delta: &TickDelta, depth: usize) {
+        if depth == 0 {
+            self.history.clear();
+            return;
+        }
+        self.history.push_back(delta.clone());
+        while self.history.len() > depth {
+            self.history.pop_front();
+        }
+    }
+    /// Moves every live cell by `offset`, for example to keep a drifting pattern near the origin.
+    ///
+    /// Entities keep their cells, call [`Universe::sync_positions`] to move them as well.
+    /// An alive background moves along, so the topology has to be moved with it.
+    pub fn translate(&mut self, offset: Position) {
+        let moved = |pos: Position| Position::new(pos.x + offset.x, pos.y + offset.y);
+        let moved_bounds = |bounds: Bounds| Bounds {
+            top: bounds.top + offset.y,
+            right: bounds.right + offset.x,
+            bottom: bounds.bottom + offset.y,
+            left: bounds.left + offset.x,
+        };
+        self.cells = self
+            .cells
+            .drain()
+            .map(|(pos, cell)| (moved(pos), cell))
+            .collect();
+        if !self.bounds.is_empty() {
+            self.bounds = moved_bounds(self.bounds);
+        }
+        self.background = self.background.map(moved_bounds);
+        self.walls = self.walls.drain().map(moved).collect();
+        self.frozen = self.frozen.drain().map(moved).collect();
+        self.tags = self
+            .tags
+            .drain()
+            .map(|(pos, tag)| (moved(pos), tag))
+            .collect();
+        self.decay = self
+            .decay
+            .drain()
+            .map(|(pos, remaining)| (moved(pos), remaining))
+            .collect();
+        for delta in &mut self.history {
+            for pos in &mut delta.births {
+                *pos = moved(*pos);
+            }
+            for (pos, _) in &mut delta.deaths {
+                *pos = moved(*pos);
+            }
+            delta.flipped_background = delta.flipped_background.map(moved_bounds);
+        }
+    }
+    /// Updates the [`Position`] components of all cell entities, needed after [`Universe::translate`].
+    pub fn sync_positions(&self, commands: &mut Commands) {
+        for (pos, cell) in &self.cells {
+            if let Some(entity) = cell.entity {
+                commands.entity(entity).insert(*pos);
+            }
+        }
+    }
+    /// Hides the entities of cells that died in [`Universe::step`] and gives entities to cells that don't have one, reusing hidden entities first.
+    pub fn sync_entities(&mut self, commands: &mut Commands) {
+        for entity in std::mem::take(&mut self.stale_entities) {
+            self.pool_cell_entity(commands, Some(entity));
+        }
+        let missing: Vec<Position> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.entity.is_none())
+            .map(|(pos, _)| *pos)
+            .collect();
+        for pos in missing {
+            let entity = self.reuse_cell_entity(commands, pos);
+            if let Some(cell) = self.cells.get_mut(&pos) {
+                cell.entity = Some(entity);
+            }
+        }
+        self.refresh_materials(commands);
+    }
+    /// Removes the dead cells and adds the born cells of `delta`.
+    ///
+    /// The entities of dead cells are hidden and reused for births, so a busy board doesn't spawn and despawn entities every tick.
+    fn apply_delta(&mut self, commands: &mut Commands, delta: &TickDelta) {
+        let tags = self.set_aside_tags(delta);
+        for (pos, _) in &delta.deaths {
+            if let Some(cell) = self.remove_cell(*pos) {
+                self.pool_cell_entity(commands, cell.entity);
+            }
+        }
+        // Flipping first gives the reused entities the material of the new background
+        if let Some(region) = delta.flipped_background {
+            self.flip_background(region);
+        }
+        for pos in &delta.births {
+            let entity = self.reuse_cell_entity(commands, *pos);
+            self.insert_cell(*pos, Cell::new(entity));
+        }
+        self.restore_tags(tags);
+        self.refresh_bounds();
+        self.refresh_materials(commands);
+    }
+}
+
+/// Why a live cell died during a tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeathCause {
+    /// The cell had fewer neighbors than it needs to live
+    Underpopulation,
+    /// The cell had more neighbors than it can live with
+    Overpopulation,
+    /// The cell was outside of the bounds of a bounded or toroidal topology
+    OutOfBounds,
+}
+impl DeathCause {
+    /// Classifies a death by comparing the cell's live neighbor count to the largest allowed count.
+    ///
+    /// Counts that fall into a gap between allowed counts, or any count when no neighbor count is allowed, are treated as underpopulation.
+    pub fn from_neighbor_count(live_neighbors: u8, allowed_neighbors: &[u8]) -> Self {
+        match allowed_neighbors.iter().max() {
+            Some(max) if live_neighbors > *max => DeathCause::Overpopulation,
+            _ => DeathCause::Underpopulation,
+        }
+    }
+}
+
+/// More live cells were requested from [`Universe::generate_count`] than fit in the bounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyCells {
+    pub requested: usize,
+    /// The number of cells in the bounds
+    pub available: usize,
+}
+impl fmt::Display for TooManyCells {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "can't place {} live cells in bounds with only {} cells",
+            self.requested, self.available
+        )
+    }
+}
+impl Error for TooManyCells {}
+
+/// The changes a single tick made to a universe
+///
+/// Births and deaths are changes to [`Universe::cells`]. While the [background](Universe::background) is alive,
+/// a birth is a cell that stopped following it and a death one that started following it again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TickDelta {
+    /// Positions of the cells that were born
+    pub births: Vec<Position>,
+    /// Positions of the cells that died and why they died
+    pub deaths: Vec<(Position, DeathCause)>,
+    /// The rectangle of the background if it came alive or died, see [`Universe::background`]
+    pub flipped_background: Option<Bounds>,
+}
+impl TickDelta {
+    /// Whether the tick changed anything, `false` means the universe is a still life (or empty)
+    pub fn changed(&self) -> bool {
+        !self.births.is_empty() || !self.deaths.is_empty() || self.flipped_background.is_some()
+    }
+}
+
+/// How a tick runs against a background that is or becomes alive, see [`Universe::inversion`]
+struct Inversion {
+    /// The complemented rule, which runs on [`Universe::cells`] like an ordinary rule
+    config: SimulationConfig,
+    /// The rectangle of the background
+    region: Bounds,
+    /// Whether the background is alive after the tick
+    alive_after: bool,
+}
+
+impl From<&CellPattern> for Universe {
+    /// Same as [`Universe::from_pattern`] starting at generation 0
+    fn from(pattern: &CellPattern) -> Self {
+        Universe::from_pattern(pattern, None)
+    }
+}
+impl From<CellPattern> for Universe {
+    fn from(pattern: CellPattern) -> Self {
+        Universe::from_positions(pattern.cells)
+    }
+}
+impl From<&Universe> for CellPattern {
+    /// Same as [`Universe::to_pattern`]
+    fn from(universe: &Universe) -> Self {
+        universe.to_pattern()
+    }
+}
+
+/// Reusable buffers for [`Universe::tick_with_scratch`]
+#[derive(Clone, Debug, Default)]
+pub struct TickScratch {
+    /// The changes of the latest tick
+    delta: TickDelta,
+    /// The dead cells that were already checked for births
+    visited: HashSet<Position>,
+}
+impl TickScratch {
+    /// Creates empty buffers, which grow to fit the universe during the first ticks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The cells whose neighbor counts include `pos`, which are its ordinary neighbors unless weights are used.
+///
+/// Walks the offsets directly instead of collecting them, as this runs for every live cell each tick.
+fn influenced_by(pos: Position, config: &SimulationConfig) -> impl Iterator<Item = Position> + '_ {
+    let neighbors = config.neighbor_weights.is_none().then(|| {
+        config
+            .neighborhood
+            .offsets()
+            .iter()
+            .map(move |(dx, dy)| Position::new(pos.x + dx, pos.y + dy))
+    });
+    // Weighted neighbors influence the cells at the opposite offsets
+    let weighted = config.neighbor_weights.iter().flat_map(move |weights| {
+        weights
+            .weights
+            .iter()
+            .filter(|(_, weight)| *weight != 0)
+            .map(move |(offset, _)| Position::new(pos.x - offset.x, pos.y - offset.y))
+    });
+    neighbors
+        .into_iter()
+        .flatten()
+        .chain(weighted)
+        .filter_map(move |candidate| match config.topology.resolve(candidate) {
+            NeighborLookup::At(resolved) => Some(resolved),
+            NeighborLookup::Alive | NeighborLookup::Dead => None,
+        })
+}
+
+/// Counts down the decay of earlier deaths and starts it for the cells that died in `delta`
+fn update_decay(decay: &mut HashMap<Position, u8>, delta: &TickDelta, generations: u8) {
+    if generations == 0 {
+        decay.clear();
+        return;
+    }
+    decay.retain(|_, remaining| {
+        *remaining -= 1;
+        *remaining > 0
+    });
+    for pos in &delta.births {
+        decay.remove(pos);
+    }
+    for (pos, _) in &delta.deaths {
+        decay.insert(*pos, generations);
+    }
+}
+
+/// Counts the neighbors of `pos` in `neighborhood` for which `alive` holds, applying the out-of-bounds policy of `topology`
+fn count_neighbors(
+    pos: Position,
+    topology: &Topology,
+    neighborhood: Neighborhood,
+    alive: impl Fn(Position) -> bool,
+) -> u8 {
+    let mut count = 0;
+    for (dx, dy) in neighborhood.offsets() {
+        let alive = match topology.resolve(Position::new(pos.x + dx, pos.y + dy)) {
+            NeighborLookup::At(resolved) => alive(resolved),
+            NeighborLookup::Alive => true,
+            NeighborLookup::Dead => false,
+        };
+        if alive {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Sums the weights of the neighbors of `pos` for which `alive` holds, see [`count_neighbors`]
+fn sum_neighbor_weights(
+    pos: Position,
+    topology: &Topology,
+    weights: &NeighborWeights,
+    alive: impl Fn(Position) -> bool,
+) -> i32 {
+    let mut sum = 0;
+    for (offset, weight) in &weights.weights {
+        let alive = match topology.resolve(Position::new(pos.x + offset.x, pos.y + offset.y)) {
+            NeighborLookup::At(resolved) => alive(resolved),
+            NeighborLookup::Alive => true,
+            NeighborLookup::Dead => false,
+        };
+        if alive {
+            sum += weight;
+        }
+    }
+    sum
+}
+
+/// Every position in `region`, row by row from the bottom left
+fn region_positions(region: Bounds) -> impl Iterator<Item = Position> {
+    (region.bottom..=region.top)
+        .flat_map(move |y| (region.left..=region.right).map(move |x| Position::new(x, y)))
+}
+
+/// The bounds of `cells`, found by checking every cell
+fn scan_bounds(cells: &Cells) -> Bounds {
+    let mut bounds = Bounds::empty();
+    for pos in cells.keys() {
+        bounds.include(*pos);
+    }
+    bounds
+}
+
+/// Rolls each cell in a `size` rectangle centered on the origin, row by row from the bottom left.
+fn random_positions(size: SizeInt, life_chance: f32, rng: &mut impl Rng) -> Vec<Position> {
+    let half_size = SizeInt::new(
+        (size.width as f32 / 2.0) as i32,
+        (size.height as f32 / 2.0) as i32,
+    );
+    // Reserve room for the expected population up front to avoid repeated reallocations on big boards
+    let area = SizeInt::new(2 * half_size.width, 2 * half_size.height)
+        .checked_area()
+        .unwrap_or(0) as usize;
+    let expected_population = (area as f32 * life_chance.clamp(0.0, 1.0)).ceil() as usize;
+    let mut positions = Vec::with_capacity(expected_population);
+    for y in -half_size.height..half_size.height {
+        for x in -half_size.width..half_size.width {
+            let lives = rng.gen::<f32>() < life_chance;
+            if lives {
+                positions.push(Position::new(x, y));
+            }
+        }
+    }
+    positions
+}
+
+impl Universe {
+    /// Draws the universe as a hexagonal grid, see [`Neighborhood::Hex`].
+    ///
+    /// Like the [`Display`](fmt::Display) output, but with a space between cells and every row shifted half a cell right of the row below it.
+    pub fn to_hex_string(&self) -> String {
+        let bounds = self.bounds();
+        let mut output = String::new();
+        for y in (bounds.bottom..bounds.top + 1).rev() {
+            output.push('\n');
+            output.push_str(&" ".repeat((y - bounds.bottom) as usize));
+            for x in bounds.left..bounds.right + 1 {
+                let symbol = if self.state_at(Position::new(x, y)) == CellState::Alive {
+                    '◼'
+                } else {
+                    '◻'
+                };
+                output.push(symbol);
+                output.push(' ');
+            }
+        }
+        output
+    }
+
+    /// Draws the cells inside `bounds` with the given characters, one line per row from top to bottom.
+    ///
+    /// Unlike the [`Display`](fmt::Display) output, the viewport is fixed by the caller
+    /// and every row ends with a newline, which keeps the text stable for tests and docs.
+    pub fn as_ascii_art(&self, bounds: Bounds, alive: char, dead: char) -> String {
+        let mut output = String::new();
+        for y in (bounds.bottom..=bounds.top).rev() {
+            for x in bounds.left..=bounds.right {
+                let symbol = if self.state_at(Position::new(x, y)) == CellState::Alive {
+                    alive
+                } else {
+                    dead
+                };
+                output.push(symbol);
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bounds = self.bounds();
+        info!("{:?}", bounds);
+        for y in (bounds.bottom..bounds.top + 1).rev() {
+            write!(f, "\n")?;
+            for x in bounds.left..bounds.right + 1 {
+                let symbol = match self.state_at(Position::new(x, y)) {
+                    CellState::Alive => '◼',
+                    CellState::Dead => '◻',
+                };
+                write!(f, "{}", symbol)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{rule::Rule, GenerationConfig, SimulationConfig};
+
+    fn universe_with(commands: &mut Commands, positions: &[Position]) -> Universe {
+        let mut universe = Universe::default();
+        universe.toggle_cells_at(commands, positions.to_vec());
+        universe
+    }
+
+    fn rect(left: i32, bottom: i32, width: i32, height: i32) -> Vec<Position> {
+        (bottom..bottom + height)
+            .flat_map(|y| (left..left + width).map(move |x| Position::new(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn bounds_fit_into_viewport() {
+        let bounds = Bounds {
+            top: 4,
+            right: 9,
+            bottom: 0,
+            left: 0,
+        };
+        assert_eq!(bounds.aspect_ratio(), 2.0);
+        assert_eq!(Bounds::empty().aspect_ratio(), 1.0);
+        let (scale, offset) = bounds.fit_into(SizeFloat::new(100.0, 100.0));
+        assert_eq!(scale, 10.0);
+        assert_eq!(offset, Vec2::new(0.0, 25.0));
+    }
+
+    #[test]
+    fn bounds_union_and_intersection() {
+        let first = Bounds {
+            top: 0,
+            right: 0,
+            bottom: 0,
+            left: 0,
+        };
+        let second = Bounds {
+            top: 5,
+            right: 8,
+            bottom: 5,
+            left: 8,
+        };
+        let union = first.union(&second);
+        assert_eq!(
+            union,
+            Bounds {
+                top: 5,
+                right: 8,
+                bottom: 0,
+                left: 0,
+            }
+        );
+        assert_eq!(first.intersection(&second), None);
+        assert_eq!(union.intersection(&second), Some(second));
+
+        let empty = Universe::default().bounds();
+        assert!(empty.is_empty());
+        assert_eq!(empty.union(&first), first);
+        assert_eq!(first.union(&empty), first);
+        assert_eq!(empty.intersection(&first), None);
+    }
+
+    #[test]
+    fn cached_bounds_match_a_rescan() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig {
+            history_depth: 10,
+            ..Default::default()
+        };
+        let mut universe = universe_with(&mut commands, &rect(0, 0, 3, 1));
+        universe.toggle_cells_at(
+            &mut commands,
+            vec![Position::new(10, 5), Position::new(-4, -2)],
+        );
+        assert_eq!(universe.bounds(), scan_bounds(&universe.cells));
+        // Removing the cells on the edges shrinks the bounds back
+        universe.toggle_cells_at(
+            &mut commands,
+            vec![Position::new(10, 5), Position::new(-4, -2)],
+        );
+        assert_eq!(universe.bounds(), scan_bounds(&universe.cells));
+        for _ in 0..3 {
+            universe.tick(&mut commands, &config);
+            assert_eq!(universe.bounds(), scan_bounds(&universe.cells));
+        }
+        universe.toggle_cells_at(&mut commands, CellPattern::glider().cells);
+        universe.advance(8, &config);
+        assert_eq!(universe.bounds(), scan_bounds(&universe.cells));
+        universe.step_back_n(&mut commands, 5);
+        assert_eq!(universe.bounds(), scan_bounds(&universe.cells));
+    }
+
+    #[test]
+    fn edits_clear_the_history() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig {
+            history_depth: 10,
+            ..Default::default()
+        };
+        let mut blinker = universe_with(&mut commands, &rect(0, 0, 3, 1));
+        blinker.tick(&mut commands, &config);
+        assert_eq!(blinker.history_len(), 1);
+
+        // Undoing the tick would bring back the ends of the blinker next to the new cell
+        let drawn = Position::new(-1, 1);
+        blinker.toggle_cells_at(&mut commands, vec![drawn]);
+        assert_eq!(blinker.history_len(), 0);
+        assert!(!blinker.step_back(&mut commands));
+        assert_eq!(blinker.population(), 4);
+        assert!(blinker.cells.contains_key(&drawn));
+        assert_eq!(blinker.generation(), 1);
+
+        blinker.tick(&mut commands, &config);
+        blinker.fill_rect(
+            &mut commands,
+            Bounds {
+                top: 6,
+                right: 6,
+                bottom: 5,
+                left: 5,
+            },
+            true,
+        );
+        assert!(!blinker.step_back(&mut commands));
+    }
+
+    #[test]
+    fn state_at_reflects_membership() {
+        let universe = Universe::from_pattern(&CellPattern::glider(), None);
+        assert_eq!(universe.state_at(Position::new(1, 2)), CellState::Alive);
+        assert_eq!(universe.state_at(Position::new(1, 0)), CellState::Dead);
+    }
+
+    #[test]
+    fn tags_follow_surviving_cells() {
+        let config = SimulationConfig::default();
+        // A blinker's middle cell survives while its ends die and are born again
+        let mut blinker = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        for pos in [Position::new(0, 0), Position::new(1, 0)] {
+            assert!(blinker.set_tag(pos, Some(7)));
+        }
+        assert!(!blinker.set_tag(Position::new(1, 1), Some(7)));
+
+        let next = blinker.next_generation(&config);
+        assert_eq!(next.tag_at(Position::new(1, 0)), Some(7));
+        assert_eq!(next.tag_at(Position::new(1, 1)), None);
+
+        blinker.advance(2, &config);
+        assert_eq!(blinker.tag_at(Position::new(1, 0)), Some(7));
+        assert_eq!(blinker.tag_at(Position::new(0, 0)), None);
+        assert_eq!(blinker.fork().tag_at(Position::new(1, 0)), Some(7));
+
+        blinker.translate(Position::new(3, 3));
+        assert_eq!(blinker.tag_at(Position::new(4, 3)), Some(7));
+        // Tags live beside the cells, so they don't make every cell bigger
+        assert_eq!(
+            std::mem::size_of::<Cell>(),
+            std::mem::size_of::<Option<Entity>>()
+        );
+    }
+
+    #[test]
+    fn toggling_flips_the_state() {
+        assert_eq!(CellState::Dead.toggle(), CellState::Alive);
+        assert_eq!(CellState::Alive.toggle(), CellState::Dead);
+        assert_eq!(CellState::Alive.toggle().toggle(), CellState::Alive);
+    }
+
+    #[test]
+    fn census_sums_to_population() {
+        let universe = Universe::random(SizeInt::new(20, 20), 0.4, &mut StdRng::seed_from_u64(5));
+        let counts = universe.count_by_state();
+        assert_eq!(counts.values().sum::<usize>(), universe.population());
+        assert_eq!(counts[&CellKind::Alive], universe.population());
+        assert!(Universe::default().count_by_state().is_empty());
+    }
+
+    #[test]
+    fn patterns_can_start_at_a_later_generation() {
+        let config = SimulationConfig::default();
+        let mut universe = Universe::from_pattern(&CellPattern::glider(), Some(3));
+        assert_eq!(universe.generation(), 3);
+        universe.advance(2, &config);
+        assert_eq!(universe.generation(), 5);
+        assert_eq!(
+            Universe::from_pattern(&CellPattern::glider(), None).generation(),
+            0
+        );
+    }
+
+    #[test]
+    fn translating_back_restores_cells() {
+        let universe = Universe::random(SizeInt::new(10, 10), 0.5, &mut StdRng::seed_from_u64(8));
+        let mut translated = universe.clone();
+        translated.translate(Position::new(7, -3));
+        let bounds = universe.bounds();
+        assert_eq!(
+            translated.bounds(),
+            Bounds {
+                top: bounds.top - 3,
+                right: bounds.right + 7,
+                bottom: bounds.bottom - 3,
+                left: bounds.left + 7,
+            }
+        );
+        translated.translate(Position::new(-7, 3));
+        assert_eq!(translated.to_csv(), universe.to_csv());
+        assert_eq!(translated.bounds(), bounds);
+    }
+
+    #[test]
+    fn neighbors_are_clipped_to_bounds() {
+        let bounds = Bounds {
+            top: 2,
+            right: 2,
+            bottom: 0,
+            left: 0,
+        };
+        let mut corner = Universe::neighbors_within(Position::new(0, 0), &bounds);
+        corner.sort();
+        assert_eq!(
+            corner,
+            vec![
+                Position::new(0, 1),
+                Position::new(1, 0),
+                Position::new(1, 1)
+            ]
+        );
+        assert_eq!(
+            Universe::neighbors_within(Position::new(1, 1), &bounds).len(),
+            8
+        );
+        assert!(Universe::neighbors_within(Position::new(9, 9), &bounds).is_empty());
+    }
+
+    #[test]
+    fn histogram_counts_live_neighbors() {
+        let block = Universe::from_positions(rect(0, 0, 2, 2));
+        assert_eq!(
+            block.neighbor_count_histogram(),
+            [0, 0, 0, 4, 0, 0, 0, 0, 0]
+        );
+        let blinker = Universe::from_positions(rect(0, 0, 3, 1));
+        assert_eq!(
+            blinker.neighbor_count_histogram(),
+            [0, 2, 1, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn counts_cells_in_regions() {
+        let glider = Universe::from(&CellPattern::glider());
+        let region = |left, bottom, right, top| Bounds {
+            top,
+            right,
+            bottom,
+            left,
+        };
+        assert_eq!(glider.count_in_region(region(-5, -5, 5, 5)), 5);
+        assert_eq!(glider.count_in_region(region(0, 0, 2, 2)), 5);
+        // Partially outside, only overlapping the bottom left cell
+        assert_eq!(glider.count_in_region(region(-1, -1, 1, 0)), 1);
+        assert_eq!(glider.count_in_region(region(1, 0, 2, 1)), 3);
+        assert_eq!(glider.count_in_region(Bounds::empty()), 0);
+        // Regions smaller than the population are scanned cell by cell
+        let full = Universe::full(SizeInt::new(20, 20));
+        assert_eq!(full.count_in_region(region(3, 3, 4, 4)), 4);
+    }
+
+    #[test]
+    fn density_of_regions() {
+        let full = Universe::full(SizeInt::new(10, 10));
+        assert_eq!(full.density(full.bounds()), 1.0);
+        let glider = Universe::from(&CellPattern::glider());
+        assert!((glider.density(glider.bounds()) - 5.0 / 9.0).abs() < f32::EPSILON);
+        let far_away = Bounds {
+            top: 100,
+            right: 100,
+            bottom: 90,
+            left: 90,
+        };
+        assert_eq!(glider.density(far_away), 0.0);
+        assert_eq!(glider.density(Bounds::empty()), 0.0);
+        assert_eq!(far_away.area(), 121);
+    }
+
+    #[test]
+    fn clusters_are_connected_through_corners() {
+        let mut positions = rect(0, 0, 2, 2);
+        // Only touches the block diagonally
+        positions.push(Position::new(2, 2));
+        positions.extend(CellPattern::glider().translated(Position::new(10, 0)).cells);
+        positions.push(Position::new(-5, -5));
+        let clusters = Universe::from_positions(positions).clusters();
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0], vec![Position::new(-5, -5)]);
+        assert_eq!(clusters[1].len(), 5);
+        assert!(clusters[1].contains(&Position::new(2, 2)));
+        assert_eq!(clusters[2][0], Position::new(10, 0));
+        assert!(Universe::default().clusters().is_empty());
+    }
+
+    #[test]
+    fn outlines_leave_out_enclosed_cells() {
+        let square = Universe::full(SizeInt::new(3, 3));
+        let outline = square.outline();
+        assert_eq!(outline.len(), 8);
+        assert!(!outline.contains(&Position::new(1, 1)));
+        assert!(outline.contains(&Position::new(0, 0)));
+
+        let glider = Universe::from(&CellPattern::glider());
+        assert_eq!(glider.outline().len(), 5);
+        assert!(Universe::default().outline().is_empty());
+    }
+
+    #[test]
+    fn inverting_twice_restores_the_region() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut universe = universe_with(&mut commands, &CellPattern::glider().cells);
+        let original = universe.fork();
+        let region = Bounds {
+            top: 3,
+            right: 1,
+            bottom: -1,
+            left: -2,
+        };
+        universe.invert(&mut commands, region);
+        // 20 cells in the region, 3 of them were alive
+        assert_eq!(universe.population(), 17 + 2);
+        assert_eq!(universe.state_at(Position::new(1, 1)), CellState::Dead);
+        assert_eq!(universe.state_at(Position::new(-2, -1)), CellState::Alive);
+        universe.invert(&mut commands, region);
+        assert!(universe.diff(&original).is_empty());
+    }
+
+    #[test]
+    fn ticks_reuse_the_entities_of_dead_cells() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+        let (_, gun) = CellPattern::by_name("Gosper glider gun").unwrap();
+        let mut universe = universe_with(&mut commands, &gun.cells);
+        let mut seen = HashSet::new();
+        let mut peak_population = universe.population();
+        let mut births = 0;
+        for _ in 0..120 {
+            births += universe.tick(&mut commands, &config).births.len();
+            peak_population = peak_population.max(universe.population());
+            seen.extend(universe.cells.values().filter_map(|cell| cell.entity));
+            // Entities are only spawned when the population outgrows every entity spawned so far
+            assert_eq!(
+                universe.population() + universe.entity_pool.len(),
+                peak_population
+            );
+        }
+        assert!(seen.len() <= peak_population);
+        assert!(births > 10 * peak_population);
+        universe.clear_entity_pool(&mut commands);
+        assert!(universe.entity_pool.is_empty());
+    }
+
+    #[test]
+    fn trimming_keeps_only_cells_inside_the_bounds() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let inside = [
+            Position::new(0, 0),
+            Position::new(2, 2),
+            Position::new(-1, 1),
+        ];
+        let outside = [
+            Position::new(3, 0),
+            Position::new(0, -2),
+            Position::new(-5, 5),
+        ];
+        let mut universe = universe_with(
+            &mut commands,
+            &[inside.as_slice(), outside.as_slice()].concat(),
+        );
+        universe.trim(
+            &mut commands,
+            Bounds {
+                top: 2,
+                right: 2,
+                bottom: 0,
+                left: -1,
+            },
+        );
+        assert_eq!(universe.population(), inside.len());
+        for pos in inside {
+            assert_eq!(universe.state_at(pos), CellState::Alive);
+        }
+        assert_eq!(
+            universe.bounds(),
+            Bounds {
+                top: 2,
+                right: 2,
+                bottom: 0,
+                left: -1,
+            }
+        );
+    }
+
+    #[test]
+    fn fill_and_clear_rects() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut universe = universe_with(&mut commands, &[Position::new(3, 3)]);
+        let square = Bounds {
+            top: 9,
+            right: 9,
+            bottom: 0,
+            left: 0,
+        };
+        universe.fill_rect(&mut commands, square, true);
+        assert_eq!(universe.population(), 100);
+        universe.fill_rect(&mut commands, square, true);
+        assert_eq!(universe.population(), 100);
+        assert_eq!(universe.bounds(), square);
+
+        universe.clear_rect(
+            &mut commands,
+            Bounds {
+                top: 9,
+                right: 9,
+                bottom: 5,
+                left: 0,
+            },
+        );
+        assert_eq!(universe.population(), 50);
+        assert_eq!(universe.bounds().top, 4);
+        assert!(universe.cells.values().all(|cell| cell.entity.is_some()));
+    }
+
+    #[test]
+    fn sprinkling_fills_dead_cells() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut universe = universe_with(&mut commands, &rect(0, 0, 2, 2));
+        let region = universe.bounds().with_padding(2);
+        universe.sprinkle(&mut commands, region, 0.0, &mut rng);
+        assert_eq!(universe.population(), 4);
+        universe.sprinkle(&mut commands, region, 1.0, &mut rng);
+        assert_eq!(universe.population(), 36);
+        assert_eq!(universe.bounds(), region);
+    }
+
+    #[test]
+    fn next_generation_leaves_self_unchanged() {
+        let config = SimulationConfig::default();
+        let glider = Universe::from(&CellPattern::glider());
+        let before = glider.to_csv();
+        let next = glider.next_generation(&config);
+        assert_eq!(glider.to_csv(), before);
+        assert_eq!(glider.generation(), 0);
+
+        let mut stepped = glider.fork();
+        stepped.step(&config);
+        assert!(next.diff(&stepped).is_empty());
+        assert_eq!(next.generation(), 1);
+        assert_eq!(next.bounds(), stepped.bounds());
+    }
+
+    #[test]
+    fn nothing_is_born_without_birth_conditions() {
+        let mut config = SimulationConfig::default();
+        config.set_rule(Rule::parse("B/S23").unwrap());
+        let mut soup = Universe::random(SizeInt::new(16, 16), 0.4, &mut StdRng::seed_from_u64(5));
+        for _ in 0..10 {
+            assert!(soup.step(&config).births.is_empty());
+        }
+    }
+
+    #[test]
+    fn nothing_survives_under_seeds() {
+        let mut config = SimulationConfig::default();
+        config.set_rule(Rule::seeds());
+        let soup = Universe::random(SizeInt::new(16, 16), 0.3, &mut StdRng::seed_from_u64(2));
+        let delta = soup.tick_delta(&config);
+        assert_eq!(delta.deaths.len(), soup.population());
+        assert!(delta
+            .deaths
+            .iter()
+            .all(|(_, cause)| *cause == DeathCause::Underpopulation));
+        assert!(!delta.births.is_empty());
+        assert!(delta
+            .births
+            .iter()
+            .all(|pos| soup.live_neighbor_count(*pos) == 2));
+    }
+
+    #[test]
+    fn tick_reports_death_causes() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        // The center has 8 neighbors, edges 5 and corners 3
+        let mut universe = universe_with(&mut commands, &rect(0, 0, 3, 3));
+        universe.toggle_cells_at(&mut commands, vec![Position::new(10, 10)]);
+
+        let delta = universe.tick(&mut commands, &SimulationConfig::default());
+        let cause_at = |pos: Position| {
+            delta
+                .deaths
+                .iter()
+                .find(|(death_pos, _)| *death_pos == pos)
+                .map(|(_, cause)| *cause)
+        };
+        assert_eq!(
+            cause_at(Position::new(1, 1)),
+            Some(DeathCause::Overpopulation)
+        );
+        assert_eq!(
+            cause_at(Position::new(1, 0)),
+            Some(DeathCause::Overpopulation)
+        );
+        assert_eq!(
+            cause_at(Position::new(10, 10)),
+            Some(DeathCause::Underpopulation)
+        );
+        assert_eq!(cause_at(Position::new(0, 0)), None);
+        assert_eq!(delta.deaths.len(), 6);
+        assert_eq!(
+            delta.births,
+            vec![
+                Position::new(-1, 1),
+                Position::new(1, -1),
+                Position::new(1, 3),
+                Position::new(3, 1)
+            ]
+        );
+        assert_eq!(universe.cells.len(), 8);
+    }
+
+    #[test]
+    fn tick_reports_whether_anything_changed() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+
+        let mut block = universe_with(&mut commands, &rect(0, 0, 2, 2));
+        assert!(!block.tick(&mut commands, &config).changed());
+
+        let mut blinker = universe_with(&mut commands, &rect(0, 0, 3, 1));
+        assert!(blinker.tick(&mut commands, &config).changed());
+        assert!(blinker.tick(&mut commands, &config).changed());
+
+        let mut empty = Universe::default();
+        assert!(!empty.tick(&mut commands, &config).changed());
+    }
+
+    #[test]
+    fn distance_field_uses_chebyshev_distance() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let universe = universe_with(&mut commands, &[Position::new(0, 0)]);
+        let bounds = Bounds {
+            top: 3,
+            right: 3,
+            bottom: -3,
+            left: -3,
+        };
+
+        let field = universe.distance_field(bounds, 2);
+        assert_eq!(field.get(&Position::new(0, 0)), None);
+        assert_eq!(field.get(&Position::new(1, 1)), Some(&1));
+        assert_eq!(field.get(&Position::new(-2, 1)), Some(&2));
+        assert_eq!(field.get(&Position::new(3, 0)), None);
+        assert_eq!(field.len(), 24);
+
+        // The live cell is outside of these bounds but still the nearest one
+        let shifted = Bounds {
+            top: 3,
+            right: 3,
+            bottom: 1,
+            left: 1,
+        };
+        let field = universe.distance_field(shifted, 2);
+        assert_eq!(field.get(&Position::new(1, 1)), Some(&1));
+        assert_eq!(field.len(), 4);
+    }
+
+    #[test]
+    fn advance_defers_entity_changes_until_sync() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+        let glider = CellPattern::glider().cells;
+
+        let mut rendered = universe_with(&mut commands, &glider);
+        let mut stepped = Universe::from_positions(glider);
+        rendered.advance(4, &config);
+        for _ in 0..4 {
+            stepped.step(&config);
+        }
+        let mut rendered_positions: Vec<Position> = rendered.cells.keys().copied().collect();
+        let mut stepped_positions: Vec<Position> = stepped.cells.keys().copied().collect();
+        rendered_positions.sort();
+        stepped_positions.sort();
+        assert_eq!(rendered_positions, stepped_positions);
+
+        // Every original glider cell has died by now
+        assert_eq!(rendered.stale_entities.len(), 5);
+        assert!(rendered.cells.values().any(|cell| cell.entity.is_none()));
+        rendered.sync_entities(&mut commands);
+        assert!(rendered.stale_entities.is_empty());
+        assert!(rendered.cells.values().all(|cell| cell.entity.is_some()));
+    }
+
+    #[test]
+    fn dead_cells_decay_one_step_per_tick() {
+        let config = SimulationConfig {
+            dead_decay_generations: 3,
+            history_depth: 10,
+            ..Default::default()
+        };
+        let lonely = Position::new(10, 10);
+        let mut universe = Universe::from_positions(vec![lonely]);
+        assert_eq!(universe.dead_decay(lonely), None);
+        let mut decays = vec![];
+        for _ in 0..4 {
+            universe.step(&config);
+            decays.push(universe.dead_decay(lonely));
+        }
+        assert_eq!(decays, vec![Some(3), Some(2), Some(1), None]);
+
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        universe.step_back_n(&mut commands, 2);
+        assert_eq!(universe.dead_decay(lonely), Some(2));
+
+        // Reviving a decaying cell by hand ends its decay
+        universe.toggle_positions([lonely]);
+        assert_eq!(universe.dead_decay(lonely), None);
+        universe.step(&config);
+        assert_eq!(universe.dead_decay(lonely), Some(3));
+
+        // The ends of a blinker die and are born again every other tick
+        let mut blinker = Universe::from_positions(vec![
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        blinker.step(&config);
+        assert_eq!(blinker.dead_decay(Position::new(0, 0)), Some(3));
+        blinker.step(&config);
+        assert_eq!(blinker.dead_decay(Position::new(0, 0)), None);
+        assert_eq!(blinker.dead_decay(Position::new(1, 1)), Some(3));
+    }
+
+    #[test]
+    fn step_back_n_stops_when_history_runs_out() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig {
+            history_depth: 3,
+            ..Default::default()
+        };
+        let mut universe = universe_with(&mut commands, &CellPattern::glider().cells);
+        let mut expected = Universe::from_positions(CellPattern::glider().cells);
+        expected.advance(2, &SimulationConfig::default());
+
+        for _ in 0..5 {
+            universe.tick(&mut commands, &config);
+        }
+        assert_eq!(universe.history_len(), 3);
+        assert_eq!(universe.generation(), 5);
+        assert_eq!(universe.step_back_n(&mut commands, 10), 3);
+        assert_eq!(universe.to_string(), expected.to_string());
+        assert!(universe.stale_entities.is_empty());
+        assert!(universe.cells.values().all(|cell| cell.entity.is_some()));
+        assert!(!universe.step_back(&mut commands));
+        assert_eq!(universe.generation(), 2);
+    }
+
+    #[test]
+    fn hex_domino_oscillates() {
+        let mut config = SimulationConfig {
+            neighborhood: Neighborhood::Hex,
+            ..Default::default()
+        };
+        config.set_rule(Rule::parse("B2/S34").unwrap());
+        let domino = [Position::new(0, 0), Position::new(1, 0)];
+        let mut universe = Universe::from_positions(domino);
+        let delta = universe.step(&config);
+        assert_eq!(
+            delta.births,
+            vec![Position::new(0, 1), Position::new(1, -1)]
+        );
+        assert_eq!(universe.to_hex_string(), "\n  ◼ ◻ \n ◻ ◻ \n◻ ◼ ");
+        universe.step(&config);
+        assert_eq!(
+            universe.to_string(),
+            Universe::from_positions(domino).to_string()
+        );
+    }
+
+    #[test]
+    fn edge_neighbors_depend_on_topology() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let universe = universe_with(
+            &mut commands,
+            &[
+                Position::new(0, 2),
+                Position::new(4, 2),
+                Position::new(4, 1),
+            ],
+        );
+        let bounds = Bounds {
+            top: 4,
+            right: 4,
+            bottom: 0,
+            left: 0,
+        };
+        let edge = Position::new(0, 2);
+
+        assert_eq!(
+            universe.live_neighbor_count_in(edge, &Topology::Infinite),
+            0
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(edge, &Topology::Bounded(bounds)),
+            0
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(edge, &Topology::Toroidal(bounds)),
+            2
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(Position::new(4, 2), &Topology::Bounded(bounds)),
+            1
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(Position::new(4, 2), &Topology::Toroidal(bounds)),
+            2
+        );
+    }
+
+    #[test]
+    fn drawing_past_the_edge_of_a_torus_wraps() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let bounds = Bounds {
+            top: 4,
+            right: 4,
+            bottom: 0,
+            left: 0,
+        };
+        let torus = Topology::Toroidal(bounds);
+        let past_right_edge = Position::new(5, 2);
+        let mut universe = Universe::default();
+        universe.toggle_cells_at(&mut commands, vec![torus.wrap_position(past_right_edge)]);
+        assert_eq!(universe.state_at(Position::new(0, 2)), CellState::Alive);
+        assert_eq!(universe.population(), 1);
+        assert_eq!(
+            torus.wrap_position(Position::new(-1, -1)),
+            Position::new(4, 4)
+        );
+        assert_eq!(
+            Topology::Infinite.wrap_position(past_right_edge),
+            past_right_edge
+        );
+        assert_eq!(
+            Topology::Bounded(bounds).wrap_position(past_right_edge),
+            past_right_edge
+        );
+    }
+
+    #[test]
+    fn glider_circles_a_small_torus() {
+        let config = SimulationConfig {
+            topology: Topology::Toroidal(Bounds {
+                top: 7,
+                right: 7,
+                bottom: 0,
+                left: 0,
+            }),
+            ..Default::default()
+        };
+        let start = Universe::from_pattern(&CellPattern::glider(), None);
+        let mut universe = start.fork();
+        // A glider moves one cell diagonally every 4 generations, so it crosses the 8 cells of the torus in 32
+        for generation in 1..=32 {
+            universe.step(&config);
+            assert_eq!(universe.population(), 5);
+            assert_eq!(universe.diff(&start).is_empty(), generation == 32);
+        }
+    }
+
+    #[test]
+    fn frozen_cells_never_die() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+
+        // A lone cell would die of underpopulation
+        let mut lonely = universe_with(&mut commands, &[Position::new(0, 0)]);
+        lonely.freeze([Position::new(0, 0), Position::new(5, 5)]);
+        assert_eq!(lonely.frozen().len(), 1);
+        lonely.advance(10, &config);
+        assert_eq!(lonely.population(), 1);
+        assert!(lonely.is_frozen(Position::new(0, 0)));
+
+        // The center of a filled square has 8 neighbors, the rest evolve as if it were an ordinary live cell
+        let square: Vec<Position> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| Position::new(x, y)))
+            .collect();
+        let mut free = universe_with(&mut commands, &square);
+        let mut frozen = universe_with(&mut commands, &square);
+        frozen.freeze([Position::new(0, 0)]);
+        free.step(&config);
+        frozen.step(&config);
+        assert!(!free.cells.contains_key(&Position::new(0, 0)));
+        let mut expected: HashSet<Position> = free.cells.keys().copied().collect();
+        expected.insert(Position::new(0, 0));
+        assert_eq!(
+            frozen.cells.keys().copied().collect::<HashSet<_>>(),
+            expected
+        );
+
+        frozen.unfreeze([Position::new(0, 0)]);
+        assert!(frozen.frozen().is_empty());
+    }
+
+    #[test]
+    fn walls_block_gliders() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+        let mut universe = Universe::from_pattern(&CellPattern::glider(), None);
+        let wall: Vec<Position> = (-20..=20).map(|y| Position::new(6, y)).collect();
+        universe.add_walls(&mut commands, wall.clone());
+        let mut reached_wall = false;
+        for _ in 0..60 {
+            universe.step(&config);
+            assert!(universe.cells.keys().all(|pos| pos.x < 6));
+            reached_wall |= universe.cells.keys().any(|pos| pos.x == 5);
+        }
+        assert!(reached_wall);
+        // The glider is annihilated into a block against the wall
+        assert_eq!(universe.population(), 4);
+        assert!(!universe.tick_delta(&config).changed());
+
+        // Live walls are crowded enough to grow cells along both sides on their own
+        let config = SimulationConfig {
+            walls_count_as_alive: true,
+            ..Default::default()
+        };
+        let mut universe = Universe::default();
+        universe.add_walls(&mut commands, wall);
+        let delta = universe.step(&config);
+        assert!(delta.births.contains(&Position::new(5, 0)));
+        assert!(delta.births.contains(&Position::new(7, 0)));
+        assert!(delta.births.iter().all(|pos| pos.x != 6));
+    }
+
+    #[test]
+    fn edits_leave_walls_dead() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut universe = Universe::default();
+        let wall = Position::new(1, 1);
+        universe.add_walls(&mut commands, [wall]);
+        let bounds = Bounds {
+            top: 2,
+            right: 2,
+            bottom: 0,
+            left: 0,
+        };
+        universe.fill_rect(&mut commands, bounds, true);
+        assert_eq!(universe.state_at(wall), CellState::Dead);
+        assert_eq!(universe.population(), 8);
+
+        universe.clear_rect(&mut commands, bounds);
+        universe.toggle_positions([wall]);
+        universe.toggle_cells_at(&mut commands, vec![wall]);
+        universe.sprinkle(&mut commands, bounds, 1.0, &mut StdRng::seed_from_u64(1));
+        universe
+            .stamp_rle(
+                &mut commands,
+                "x = 3, y = 3\n3o$3o$3o!",
+                Position::default(),
+            )
+            .unwrap();
+        assert_eq!(universe.state_at(wall), CellState::Dead);
+    }
+
+    #[test]
+    fn patterns_round_trip_through_universes() {
+        let glider = CellPattern::glider();
+        assert_eq!(Universe::from(&glider).to_pattern(), glider.normalized());
+
+        let mut universe = Universe::from(glider.clone());
+        universe.translate(Position::new(-7, 12));
+        assert_eq!(CellPattern::from(&universe), glider.normalized());
+    }
+
+    #[test]
+    fn uniform_weights_give_ordinary_counts() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let soup = random_positions(SizeInt::new(10, 10), 0.4, &mut rng);
+        let mut plain = Universe::from_positions(soup.clone());
+        let mut weighted = Universe::from_positions(soup);
+        let weights = NeighborWeights::uniform(Neighborhood::Moore);
+        for pos in rect(-6, -6, 12, 12) {
+            assert_eq!(
+                weighted.weighted_neighbor_sum(pos, &Topology::Infinite, &weights),
+                plain.live_neighbor_count(pos) as i32
+            );
+        }
+
+        let plain_config = SimulationConfig::default();
+        let weighted_config = SimulationConfig {
+            neighbor_weights: Some(weights),
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            assert_eq!(plain.step(&plain_config), weighted.step(&weighted_config));
+        }
+    }
+
+    #[test]
+    fn heavier_neighbors_count_more() {
+        let universe = Universe::from_positions(vec![Position::new(1, 0), Position::new(0, 1)]);
+        let weights = NeighborWeights::new(vec![
+            (Position::new(1, 0), 2),
+            (Position::new(0, 1), -1),
+            (Position::new(-1, 0), 5),
+        ]);
+        let origin = Position::new(0, 0);
+        assert_eq!(
+            universe.weighted_neighbor_sum(origin, &Topology::Infinite, &weights),
+            1
+        );
+
+        // The lone cell to the right of the origin is born from its weight of 2, under B2/S
+        let mut config = SimulationConfig {
+            neighbor_weights: Some(weights),
+            ..Default::default()
+        };
+        config.set_rule(Rule::parse("B2/S").unwrap());
+        let mut universe = Universe::from_positions(vec![Position::new(1, 0)]);
+        let delta = universe.step(&config);
+        assert_eq!(delta.births, vec![origin]);
+    }
+
+    #[test]
+    fn dirty_ticks_match_full_ticks_after_local_edits() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = SimulationConfig::default();
+
+        // A still board of blocks, edited next to one of them
+        let blocks = (0..10).flat_map(|i| {
+            let x = i * 5;
+            [
+                Position::new(x, 0),
+                Position::new(x + 1, 0),
+                Position::new(x, 1),
+                Position::new(x + 1, 1),
+            ]
+        });
+        let mut universe = Universe::from_positions(blocks);
+        let edit = [
+            Position::new(12, 2),
+            Position::new(13, 2),
+            Position::new(14, 2),
+        ];
+        universe.toggle_positions(edit);
+        let mut dirty: HashSet<Position> = edit.into_iter().collect();
+
+        let mut full = universe.fork();
+        for _ in 0..5 {
+            let expected = full.tick(&mut commands, &config);
+            let delta = universe.tick_dirty(&mut commands, &dirty, &config);
+            assert_eq!(delta, expected);
+            assert!(universe.diff(&full).is_empty());
+            dirty = delta
+                .births
+                .iter()
+                .chain(delta.deaths.iter().map(|(pos, _)| pos))
+                .copied()
+                .collect();
+        }
+        assert_eq!(universe.generation(), 5);
+    }
+
+    #[test]
+    fn reused_scratch_matches_allocating_ticks() {
+        let config = SimulationConfig::default();
+        let mut rng = StdRng::seed_from_u64(9);
+        let soup = random_positions(SizeInt::new(12, 12), 0.4, &mut rng);
+        let mut allocating = Universe::from_positions(soup.clone());
+        let mut reusing = Universe::from_positions(soup);
+        let mut scratch = TickScratch::new();
+        for _ in 0..30 {
+            let expected = allocating.step(&config);
+            assert_eq!(reusing.tick_with_scratch(&mut scratch, &config), &expected);
+        }
+        assert!(allocating.diff(&reusing).is_empty());
+    }
+
+    #[test]
+    fn state_buffer_is_row_major_from_the_bottom_left() {
+        let universe = Universe::from(&CellPattern::glider());
+        let (size, buffer) = universe.state_slice();
+        assert_eq!(size, SizeInt::new(3, 3));
+        assert_eq!(buffer, vec![1, 0, 1, 0, 1, 1, 0, 1, 0]);
+
+        let bounds = Bounds {
+            top: 4,
+            right: 1,
+            bottom: -1,
+            left: -2,
+        };
+        let mut buffer = vec![7; 100];
+        let size = universe.write_state_buffer(bounds, &mut buffer);
+        assert_eq!(buffer.len(), (size.width * size.height) as usize);
+        for pos in rect(-2, -1, 4, 6) {
+            let index = (pos.y - bounds.bottom) * size.width + (pos.x - bounds.left);
+            let alive = universe.state_at(pos) == CellState::Alive;
+            assert_eq!(buffer[index as usize] == 1, alive);
+        }
+        assert!(Universe::default().state_slice().1.is_empty());
+    }
+
+    #[test]
+    fn ascii_art_draws_rows_from_the_top() {
+        let universe = Universe::from_positions(vec![
+            Position::new(1, 2),
+            Position::new(2, 1),
+            Position::new(0, 0),
+            Position::new(1, 0),
+            Position::new(2, 0),
+        ]);
+        let bounds = Bounds {
+            top: 3,
+            right: 3,
+            bottom: 0,
+            left: 0,
+        };
+        assert_eq!(
+            universe.as_ascii_art(bounds, '#', '.'),
+            "....\n.#..\n..#.\n###.\n"
+        );
+    }
+
+    #[test]
+    fn b0_rules_work_in_finite_topologies() {
+        let bounds = Bounds {
+            top: 5,
+            right: 5,
+            bottom: 0,
+            left: 0,
+        };
+        let mut config = SimulationConfig {
+            topology: Topology::Toroidal(bounds),
+            ..Default::default()
+        };
+        // Without survival on 8 neighbors, an empty torus flashes between empty and full
+        config.set_rule(Rule::parse("B0/S").unwrap());
+        let mut universe = Universe::default();
+        assert_eq!(universe.step(&config).flipped_background, Some(bounds));
+        assert_eq!(universe.population(), 36);
+        assert!(universe.cells.is_empty());
+        universe.step(&config);
+        assert_eq!(universe.population(), 0);
+        assert_eq!(universe.background(), None);
+
+        // InverseLife is Conway's life with live and dead cells swapped, so an inverted blinker oscillates
+        config.set_rule(Rule::parse("B0123478/S01234678").unwrap());
+        let blinker = [
+            Position::new(1, 2),
+            Position::new(2, 2),
+            Position::new(3, 2),
+        ];
+        let inverted = |holes: &[Position]| {
+            Universe::from_positions(
+                (0..=5)
+                    .flat_map(|y| (0..=5).map(move |x| Position::new(x, y)))
+                    .filter(|pos| !holes.contains(pos)),
+            )
+        };
+        let start = inverted(&blinker);
+        let mut universe = start.fork();
+        universe.step(&config);
+        let vertical = [
+            Position::new(2, 1),
+            Position::new(2, 2),
+            Position::new(2, 3),
+        ];
+        assert!(universe.diff(&inverted(&vertical)).is_empty());
+        universe.step(&config);
+        assert!(universe.diff(&start).is_empty());
+    }
+
+    #[test]
+    fn b0_ticks_only_store_the_cells_that_differ_from_the_background() {
+        let bounds = Bounds {
+            top: 99_999,
+            right: 99_999,
+            bottom: 0,
+            left: 0,
+        };
+        let mut config = SimulationConfig {
+            topology: Topology::Toroidal(bounds),
+            ..Default::default()
+        };
+        // InverseLife, so a blinker of holes in the live background keeps blinking
+        config.set_rule(Rule::parse("B0123478/S01234678").unwrap());
+        let mut universe = Universe::from_positions([]);
+        universe.step(&config);
+        assert_eq!(universe.background(), Some(bounds));
+        assert!(universe.cells.is_empty());
+        assert_eq!(universe.population(), 10_000_000_000);
+
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let holes = [
+            Position::new(1, 2),
+            Position::new(2, 2),
+            Position::new(3, 2),
+        ];
+        universe.clear_rect(
+            &mut commands,
+            Bounds {
+                top: 2,
+                right: 3,
+                bottom: 2,
+                left: 1,
+            },
+        );
+        assert_eq!(universe.cells.len(), 3);
+        assert!(holes
+            .iter()
+            .all(|pos| universe.state_at(*pos) == CellState::Dead));
+        universe.freeze([Position::new(50, 50)]);
+        universe.set_tag(Position::new(60, 60), Some(7));
+
+        for _ in 0..2 {
+            universe.step(&config);
+            assert_eq!(universe.cells.len(), 3);
+            assert_eq!(universe.state_at(Position::new(2, 1)), CellState::Dead);
+            universe.step(&config);
+            assert!(holes
+                .iter()
+                .all(|pos| universe.state_at(*pos) == CellState::Dead));
+        }
+        assert_eq!(universe.tag_at(Position::new(60, 60)), Some(7));
+        assert_eq!(universe.state_at(Position::new(50, 50)), CellState::Alive);
+
+        // Stepping back to before the background came alive empties the universe again
+        config.history_depth = 8;
+        let mut rewound = Universe::from_positions([Position::new(5, 5)]);
+        rewound.step(&config);
+        assert!(rewound.background().is_some());
+        assert!(rewound.step_back(&mut commands));
+        assert_eq!(rewound.background(), None);
+        assert_eq!(rewound.population(), 1);
+    }
+
+    #[test]
+    fn b0_matches_the_filled_in_universe() {
+        let bounds = Bounds {
+            top: 7,
+            right: 9,
+            bottom: 0,
+            left: 0,
+        };
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let topologies = [
+            Topology::Bounded(bounds),
+            Topology::Toroidal(bounds),
+            Topology::Region(bounds, OutOfBoundsPolicy::Alive),
+            Topology::Region(bounds, OutOfBoundsPolicy::Mirror),
+        ];
+        for (seed, topology) in topologies.into_iter().enumerate() {
+            for walls_count_as_alive in [false, true] {
+                let mut config = SimulationConfig {
+                    topology,
+                    walls_count_as_alive,
+                    ..Default::default()
+                };
+                config.set_rule(Rule::parse("B0123478/S34678").unwrap());
+                let mut universe = Universe::random(
+                    SizeInt::new(8, 8),
+                    0.5,
+                    &mut StdRng::seed_from_u64(seed as u64),
+                );
+                universe.add_walls(&mut commands, [Position::new(2, 2), Position::new(-1, 3)]);
+                assert_matches_filled_in(&mut universe, &config);
+            }
+        }
+    }
+
+    /// Steps `universe` and compares every cell of its topology to a brute-force tick of the filled-in background
+    fn assert_matches_filled_in(universe: &mut Universe, config: &SimulationConfig) {
+        let bounds = *config.topology.bounds().unwrap();
+        for _ in 0..12 {
+            // Every cell of a small board can be checked directly, filling in the background first
+            let mut filled = universe.fork();
+            filled.walls = universe.walls.clone();
+            filled.clear_background();
+            let mut expected = HashSet::new();
+            for pos in region_positions(bounds) {
+                let alive = filled.cells.contains_key(&pos);
+                let count = filled.live_neighbor_count_for(pos, config);
+                let next = if alive {
+                    config.survives(count)
+                } else {
+                    config.is_born(count)
+                };
+                if next && !filled.is_wall(pos) {
+                    expected.insert(pos);
+                }
+            }
+            universe.step(config);
+            assert_eq!(universe.live_positions().collect::<HashSet<_>>(), expected);
+            assert_eq!(universe.population(), expected.len());
+        }
+    }
+
+    #[test]
+    fn alive_out_of_bounds_surrounds_edge_cells() {
+        let bounds = Bounds {
+            top: 4,
+            right: 4,
+            bottom: 0,
+            left: 0,
+        };
+        let topology = Topology::Region(bounds, OutOfBoundsPolicy::Alive);
+        let mut universe = Universe::default();
+        assert_eq!(
+            universe.live_neighbor_count_in(Position::new(0, 0), &topology),
+            5
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(Position::new(0, 2), &topology),
+            3
+        );
+        assert_eq!(
+            universe.live_neighbor_count_in(Position::new(2, 2), &topology),
+            0
+        );
+
+        // Every edge cell apart from the corners has exactly 3 live neighbors
+        let config = SimulationConfig {
+            topology,
+            ..Default::default()
+        };
+        let delta = universe.step(&config);
+        assert_eq!(delta.births.len(), 12);
+        assert!(delta.births.iter().all(|pos| bounds.contains(*pos)));
+    }
+
+    #[test]
+    fn mirror_out_of_bounds_preserves_symmetry() {
+        let pattern = [
+            Position::new(0, 2),
+            Position::new(1, 2),
+            Position::new(1, 3),
+            Position::new(2, 1),
+        ];
+        let bounds = Bounds {
+            top: 9,
+            right: 9,
+            bottom: -9,
+            left: 0,
+        };
+        let mirrored_config = SimulationConfig {
+            topology: Topology::Region(bounds, OutOfBoundsPolicy::Mirror),
+            ..Default::default()
+        };
+        let mut mirrored = Universe::from_positions(pattern);
+        // The same pattern reflected across the left edge in an infinite plane
+        let mut reflected = Universe::from_positions(
+            pattern
+                .iter()
+                .flat_map(|pos| [*pos, Position::new(-pos.x - 1, pos.y)]),
+        );
+        for _ in 0..4 {
+            mirrored.step(&mirrored_config);
+            reflected.step(&SimulationConfig::default());
+            let mut expected: Vec<Position> = reflected
+                .cells
+                .keys()
+                .filter(|pos| pos.x >= 0)
+                .copied()
+                .collect();
+            let mut actual: Vec<Position> = mirrored.cells.keys().copied().collect();
+            expected.sort();
+            actual.sort();
+            assert!(!actual.is_empty());
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn bounded_topology_prevents_births_outside() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        // A vertical blinker on the left edge would grow to the left in an infinite plane
+        let mut universe = universe_with(&mut commands, &rect(0, 0, 1, 3));
+        let config = SimulationConfig {
+            topology: Topology::Bounded(Bounds {
+                top: 2,
+                right: 2,
+                bottom: 0,
+                left: 0,
+            }),
+            ..Default::default()
+        };
+        let delta = universe.tick(&mut commands, &config);
+        assert_eq!(delta.births, vec![Position::new(1, 1)]);
+        assert_eq!(universe.cells.len(), 2);
+    }
+
+    #[test]
+    fn generates_an_exact_population() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let bounds = Bounds {
+            top: 9,
+            right: 4,
+            bottom: 0,
+            left: -5,
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        for count in [0, 1, 37, 100] {
+            let universe = Universe::generate_count(
+                &mut commands,
+                Materials::default(),
+                bounds,
+                count,
+                &mut rng,
+            )
+            .unwrap();
+            assert_eq!(universe.population(), count);
+            assert!(universe.cells.keys().all(|pos| bounds.contains(*pos)));
+        }
+        let error =
+            Universe::generate_count(&mut commands, Materials::default(), bounds, 101, &mut rng)
+                .err();
+        assert_eq!(
+            error,
+            Some(TooManyCells {
+                requested: 101,
+                available: 100
+            })
+        );
+    }
+
+    #[test]
+    fn seeded_generation_is_reproducible() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let config = GenerationConfig {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let mut generate = || {
+            Universe::generate_with_rng(
+                &mut commands,
+                Materials::default(),
+                config.initial_size,
+                config.life_chance,
+                &mut config.rng(),
+            )
+        };
+        let first = generate().unwrap();
+        let second = generate().unwrap();
+        assert!(!first.cells.is_empty());
+        assert_eq!(first.to_string(), second.to_string());
+
+        let detached = Universe::random(config.initial_size, config.life_chance, &mut config.rng());
+        assert_eq!(first.to_string(), detached.to_string());
+    }
+
+    #[test]
+    fn generating_absurd_sizes_fails() {
+        let world = World::default();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let mut generate = |size| {
+            Universe::generate_seeded(&mut commands, Materials::default(), size, 0.5, 1).err()
+        };
+        assert!(matches!(
+            generate(SizeInt::new(2_000_000_000, 1_000)),
+            Some(InvalidSize::TooLarge(_))
+        ));
+        assert!(matches!(
+            generate(SizeInt::new(0, 10)),
+            Some(InvalidSize::NotPositive(_))
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn isolated_cells_all_die(lattice in prop::collection::hash_set((-6i32..6, -6i32..6), 0..30)) {
+            // Cells at least 3 apart have no neighbors, and no dead cell touches more than one of them
+            let universe =
+                Universe::from_positions(lattice.iter().map(|(x, y)| Position::new(x * 3, y * 3)));
+            let delta = universe.tick_delta(&SimulationConfig::default());
+            prop_assert!(delta.births.is_empty());
+            prop_assert_eq!(delta.deaths.len(), lattice.len());
+        }
+
+        #[test]
+        fn blocks_are_still_lifes(x in -1000i32..1000, y in -1000i32..1000) {
+            let mut universe = Universe::from_positions(rect(x, y, 2, 2));
+            let delta = universe.step(&SimulationConfig::default());
+            prop_assert!(!delta.changed());
+            prop_assert_eq!(universe.population(), 4);
+        }
+
+        #[test]
+        fn ticking_is_deterministic(cells in prop::collection::vec((-8i32..8, -8i32..8), 0..60)) {
+            let universe = Universe::from_positions(cells.iter().map(|(x, y)| Position::new(*x, *y)));
+            let config = SimulationConfig::default();
+            let delta = universe.tick_delta(&config);
+            prop_assert_eq!(&delta, &universe.fork().tick_delta(&config));
+            for pos in &delta.births {
+                prop_assert!(universe.state_at(*pos) == CellState::Dead);
+            }
+            for (pos, _) in &delta.deaths {
+                prop_assert!(universe.state_at(*pos) == CellState::Alive);
             }
         }
-        Ok(())
     }
 }
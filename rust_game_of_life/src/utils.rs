@@ -1,4 +1,21 @@
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use std::{error::Error, fmt};
+
+/// The offsets of the 8 surrounding cells, row by row starting from the bottom left
+pub const NEIGHBOR_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+/// The offsets of the 6 neighbors of a hexagonal cell, see [`Position::hex_neighbors`]
+pub const HEX_NEIGHBOR_OFFSETS: [(i32, i32); 6] =
+    [(1, 0), (-1, 0), (0, 1), (-1, 1), (0, -1), (1, -1)];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -7,20 +24,142 @@ impl Position {
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
-    pub fn neighbors(&self) -> Vec<Self> {
-        let mut neighbors: Vec<Self> = Vec::new();
-        for y in self.y - 1..self.y + 2 {
-            for x in self.x - 1..self.x + 2 {
-                if !(x == self.x && y == self.y) {
-                    neighbors.push(Self::new(x, y));
-                }
+    /// The position moved by `dx` and `dy`, `None` if either coordinate would overflow
+    pub fn checked_offset(&self, dx: i32, dy: i32) -> Option<Self> {
+        Some(Self::new(self.x.checked_add(dx)?, self.y.checked_add(dy)?))
+    }
+    /// The 8 surrounding positions
+    pub fn neighbors(&self) -> [Self; 8] {
+        NEIGHBOR_OFFSETS.map(|(dx, dy)| Self::new(self.x + dx, self.y + dy))
+    }
+    /// The 6 neighbors of a hexagonal cell, treating the position as [axial coordinates](https://www.redblobgames.com/grids/hexagons/#coordinates-axial).
+    ///
+    /// Each row is offset by half a cell to the right of the row below it,
+    /// so the neighbors above are straight up and up-left, and the ones below straight down and down-right.
+    pub fn hex_neighbors(&self) -> Vec<Self> {
+        HEX_NEIGHBOR_OFFSETS
+            .iter()
+            .map(|(dx, dy)| Self::new(self.x + dx, self.y + dy))
+            .collect()
+    }
+    /// The neighbors of the position in the given neighborhood
+    pub fn neighbors_in(&self, neighborhood: Neighborhood) -> Vec<Self> {
+        match neighborhood {
+            Neighborhood::Moore => self.neighbors().to_vec(),
+            Neighborhood::Hex => self.hex_neighbors(),
+        }
+    }
+}
+
+/// Which cells around a cell count as its neighbors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The 8 surrounding cells of a square grid
+    #[default]
+    Moore,
+    /// The 6 surrounding cells of a hexagonal grid, see [`Position::hex_neighbors`]
+    Hex,
+}
+impl Neighborhood {
+    /// The offsets of the neighbors from a cell, see [`NEIGHBOR_OFFSETS`] and [`HEX_NEIGHBOR_OFFSETS`]
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::Moore => &NEIGHBOR_OFFSETS,
+            Neighborhood::Hex => &HEX_NEIGHBOR_OFFSETS,
+        }
+    }
+}
+
+/// Integer weights for the cells around a cell, for [weighted life](https://conwaylife.com/wiki/Weighted_Life) and similar rules.
+///
+/// The rule thresholds are compared against the summed weights of the live neighbors instead of their count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NeighborWeights {
+    /// The offset of each neighbor from the cell, and how much it counts when alive
+    pub weights: Vec<(Position, i32)>,
+}
+impl NeighborWeights {
+    pub fn new(weights: Vec<(Position, i32)>) -> Self {
+        Self { weights }
+    }
+    /// A weight of 1 for every neighbor in `neighborhood`, which gives the ordinary neighbor counts
+    pub fn uniform(neighborhood: Neighborhood) -> Self {
+        Self::new(
+            Position::default()
+                .neighbors_in(neighborhood)
+                .into_iter()
+                .map(|offset| (offset, 1))
+                .collect(),
+        )
+    }
+}
+
+/// The positions on a straight line from `from` to `to`, both included, using Bresenham's algorithm
+pub fn line_positions(from: Position, to: Position) -> Vec<Position> {
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let step_x = if from.x < to.x { 1 } else { -1 };
+    let step_y = if from.y < to.y { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut pos = from;
+    let mut positions = vec![pos];
+    while pos != to {
+        let doubled_error = 2 * error;
+        if doubled_error >= dy {
+            error += dy;
+            pos.x += step_x;
+        }
+        if doubled_error <= dx {
+            error += dx;
+            pos.y += step_y;
+        }
+        positions.push(pos);
+    }
+    positions
+}
+
+/// The positions of a rectangle with opposite corners at `a` and `b`, either just the outline or filled
+pub fn rect_positions(a: Position, b: Position, filled: bool) -> Vec<Position> {
+    let (left, right) = (a.x.min(b.x), a.x.max(b.x));
+    let (bottom, top) = (a.y.min(b.y), a.y.max(b.y));
+    let mut positions = vec![];
+    for y in bottom..=top {
+        for x in left..=right {
+            if filled || x == left || x == right || y == bottom || y == top {
+                positions.push(Position::new(x, y));
             }
         }
-        neighbors
+    }
+    positions
+}
+
+/// How exported coordinates are laid out, for tools that don't share the crate's conventions.
+///
+/// The crate itself uses y-up coordinates like Bevy, while many tools such as Golly and image editors count rows downwards.
+/// Every exporter has a `_with` variant that takes a convention: [`to_csv_with`](crate::universe::Universe::to_csv_with),
+/// [`to_macrocell_with`](crate::universe::Universe::to_macrocell_with), [`to_svg_with`](crate::universe::Universe::to_svg_with) and [`to_writer_with`](crate::universe::Universe::to_writer_with), which covers RLE and snapshots too.
+/// Only CSV can be read back with a convention, through [`from_csv_with`](crate::universe::Universe::from_csv_with).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinateConvention {
+    /// Whether y grows downwards
+    pub y_down: bool,
+    /// The crate position that ends up at `(0, 0)` in the exported coordinates
+    pub origin: Position,
+}
+impl CoordinateConvention {
+    /// Converts a crate position to the exported coordinates.
+    pub fn export(&self, pos: Position) -> Position {
+        let y = pos.y - self.origin.y;
+        Position::new(pos.x - self.origin.x, if self.y_down { -y } else { y })
+    }
+    /// Converts exported coordinates back to a crate position, the inverse of [`CoordinateConvention::export`].
+    pub fn import(&self, pos: Position) -> Position {
+        let y = if self.y_down { -pos.y } else { pos.y };
+        Position::new(pos.x + self.origin.x, y + self.origin.y)
     }
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SizeInt {
     pub width: i32,
     pub height: i32,
@@ -29,9 +168,58 @@ impl SizeInt {
     pub fn new(width: i32, height: i32) -> Self {
         Self { width, height }
     }
+    /// A size whose area fits in an `i32`, failing for zero or negative dimensions and for areas that would overflow.
+    pub fn try_new(width: i32, height: i32) -> Result<Self, InvalidSize> {
+        let size = Self::new(width, height);
+        if width <= 0 || height <= 0 {
+            return Err(InvalidSize::NotPositive(size));
+        }
+        match size.checked_area() {
+            Some(_) => Ok(size),
+            None => Err(InvalidSize::TooLarge(size)),
+        }
+    }
+    /// The number of cells the size covers, `None` if it doesn't fit in an `i32`.
+    ///
+    /// Negative dimensions count as 0.
+    pub fn checked_area(&self) -> Option<i32> {
+        self.width.max(0).checked_mul(self.height.max(0))
+    }
 }
 
-#[derive(Default, Clone, Copy, PartialEq)]
+/// A [`SizeInt`] rejected by [`SizeInt::try_new`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidSize {
+    /// The width or height was zero or negative
+    NotPositive(SizeInt),
+    /// The area doesn't fit in an `i32`
+    TooLarge(SizeInt),
+}
+impl fmt::Display for InvalidSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidSize::NotPositive(size) => write!(
+                f,
+                "size {}x{} must have a positive width and height",
+                size.width, size.height
+            ),
+            InvalidSize::TooLarge(size) => write!(
+                f,
+                "size {}x{} has more than {} cells",
+                size.width,
+                size.height,
+                i32::MAX
+            ),
+        }
+    }
+}
+impl Error for InvalidSize {}
+
+/// A size with floating point dimensions.
+///
+/// This is deliberately not `Eq` or `Hash`: `NaN` isn't equal to itself and `0.0 == -0.0` despite the two having different bits,
+/// so a derived implementation would break the contracts of those traits. Use [`SizeFloat::to_bits_key`] to key maps by size instead.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct SizeFloat {
     pub width: f32,
     pub height: f32,
@@ -40,4 +228,116 @@ impl SizeFloat {
     pub fn new(width: f32, height: f32) -> Self {
         Self { width, height }
     }
+    /// The bit patterns of the width and height, usable as a `HashMap` key.
+    ///
+    /// `-0.0` is treated as `0.0` so that sizes that compare equal get the same key.
+    pub fn to_bits_key(&self) -> (u32, u32) {
+        fn bits(value: f32) -> u32 {
+            if value == 0.0 {
+                0.0f32.to_bits()
+            } else {
+                value.to_bits()
+            }
+        }
+        (bits(self.width), bits(self.height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_are_the_surrounding_cells_in_row_order() {
+        let pos = Position::new(3, -2);
+        let mut expected = Vec::new();
+        for y in -3..=-1 {
+            for x in 2..=4 {
+                if (x, y) != (3, -2) {
+                    expected.push(Position::new(x, y));
+                }
+            }
+        }
+        assert_eq!(pos.neighbors().to_vec(), expected);
+    }
+
+    #[test]
+    fn sizes_are_checked() {
+        assert_eq!(SizeInt::try_new(3, 4), Ok(SizeInt::new(3, 4)));
+        assert_eq!(SizeInt::new(3, 4).checked_area(), Some(12));
+        assert!(matches!(
+            SizeInt::try_new(0, 4),
+            Err(InvalidSize::NotPositive(_))
+        ));
+        assert!(matches!(
+            SizeInt::try_new(5, -1),
+            Err(InvalidSize::NotPositive(_))
+        ));
+        assert_eq!(SizeInt::new(2_000_000_000, 2).checked_area(), None);
+        assert!(matches!(
+            SizeInt::try_new(2_000_000_000, 2),
+            Err(InvalidSize::TooLarge(_))
+        ));
+        assert_eq!(Position::new(i32::MAX, 0).checked_offset(1, 0), None);
+        assert_eq!(
+            Position::new(1, 2).checked_offset(-1, 1),
+            Some(Position::new(0, 3))
+        );
+    }
+
+    #[test]
+    fn hex_cells_have_6_neighbors() {
+        let pos = Position::new(2, -1);
+        let neighbors = pos.neighbors_in(Neighborhood::Hex);
+        assert_eq!(neighbors.len(), 6);
+        assert!(!neighbors.contains(&pos));
+        // Neighborhood is symmetric
+        assert!(neighbors
+            .iter()
+            .all(|neighbor| neighbor.hex_neighbors().contains(&pos)));
+        assert_eq!(pos.neighbors_in(Neighborhood::Moore).len(), 8);
+    }
+
+    #[test]
+    fn lines_include_both_ends() {
+        assert_eq!(
+            line_positions(Position::new(0, 0), Position::new(4, 2)),
+            vec![
+                Position::new(0, 0),
+                Position::new(1, 1),
+                Position::new(2, 1),
+                Position::new(3, 2),
+                Position::new(4, 2),
+            ]
+        );
+        assert_eq!(
+            line_positions(Position::new(1, 1), Position::new(1, 1)),
+            vec![Position::new(1, 1)]
+        );
+        assert_eq!(
+            line_positions(Position::new(0, 3), Position::new(0, 0)).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn rects_are_outlined_or_filled() {
+        let corners = (Position::new(2, 2), Position::new(0, 0));
+        let outline = rect_positions(corners.0, corners.1, false);
+        assert_eq!(outline.len(), 8);
+        assert!(!outline.contains(&Position::new(1, 1)));
+        assert_eq!(rect_positions(corners.0, corners.1, true).len(), 9);
+    }
+
+    #[test]
+    fn equal_sizes_have_equal_bits_keys() {
+        assert_eq!(
+            SizeFloat::new(0.0, 1.5).to_bits_key(),
+            SizeFloat::new(-0.0, 1.5).to_bits_key()
+        );
+        assert_ne!(
+            SizeFloat::new(1.0, 2.0).to_bits_key(),
+            SizeFloat::new(2.0, 1.0).to_bits_key()
+        );
+    }
 }
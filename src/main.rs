@@ -1,11 +1,22 @@
 use rust_game_of_life::{
-    universe::{Materials, Universe},
-    utils::{Position, SizeFloat},
-    SimulationConfig,
+    catalog,
+    cell_patterns::CellPattern,
+    placement::PlacementMode,
+    stability::StabilityDetector,
+    universe::{Bounds, CellState, DeathCause, Materials, TickDelta, Universe},
+    utils::{line_positions, rect_positions, Position, SizeFloat},
+    SimulationConfig, SpeedPreset,
 };
 
-use bevy::{prelude::*, render::camera::Camera};
-use std::time::Duration;
+use bevy::{
+    prelude::*,
+    render::{
+        camera::Camera,
+        texture::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use rand::rngs::StdRng;
+use std::{collections::HashMap, time::Duration};
 
 struct UniverseTimer(Timer);
 
@@ -16,17 +27,338 @@ struct CursorPosition {
 
 struct DrawnPositions(Vec<Position>);
 
+/// The random number generator for editing tools, seeded from the generation config
+struct EditRng(StdRng);
+
+/// What dragging the mouse with shift held draws
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum ShapeTool {
+    /// Toggles every cell the cursor passes over, same as without shift
+    #[default]
+    Freehand,
+    /// A straight line from where the drag started to where it ended
+    Line,
+    /// A rectangle with opposite corners where the drag started and ended
+    Rect { filled: bool },
+}
+impl ShapeTool {
+    /// The positions of the shape between the start and end of a drag
+    fn positions(&self, start: Position, end: Position) -> Vec<Position> {
+        match self {
+            ShapeTool::Freehand => vec![end],
+            ShapeTool::Line => line_positions(start, end),
+            ShapeTool::Rect { filled } => rect_positions(start, end, *filled),
+        }
+    }
+    fn next(self) -> Self {
+        match self {
+            ShapeTool::Freehand => ShapeTool::Line,
+            ShapeTool::Line => ShapeTool::Rect { filled: false },
+            ShapeTool::Rect { filled: false } => ShapeTool::Rect { filled: true },
+            ShapeTool::Rect { filled: true } => ShapeTool::Freehand,
+        }
+    }
+}
+
+/// Where the current shape drag started, if one is in progress
+struct ShapeDrag(Option<Position>);
+
+/// The material of the shape previewed during a drag
+struct PreviewMaterial(Handle<ColorMaterial>);
+
+/// Marks the sprites previewing a shape during a drag
+struct ShapePreview;
+
+/// The material of the sprite drawn for an alive background
+struct BackgroundMaterial(Handle<ColorMaterial>);
+
+/// Marks the sprite covering an alive background, see [`Universe::background`]
+struct LiveBackground;
+
+/// The catalog pattern placed by clicking instead of drawing, cycled with `P`, turned with `O` and mirrored with `M`
+#[derive(Default)]
+struct Stamp {
+    /// The index of the pattern in the [catalog](catalog::catalog), stamping is off if `None`
+    catalog_index: Option<usize>,
+    /// How many quarter turns clockwise the pattern is turned
+    quarter_turns: u8,
+    /// Whether the pattern is flipped left to right before it's turned
+    mirrored: bool,
+}
+impl Stamp {
+    /// The selected pattern in the selected orientation, with its bottom left corner at the origin
+    fn pattern(&self) -> Option<CellPattern> {
+        let info = catalog::catalog().nth(self.catalog_index?)?;
+        let (_, mut pattern) = CellPattern::by_name(info.name)?;
+        pattern = pattern.normalized();
+        if self.mirrored {
+            pattern = pattern.mirrored();
+        }
+        for _ in 0..self.quarter_turns {
+            pattern = pattern.rotated_clockwise();
+        }
+        Some(pattern)
+    }
+}
+
+/// The translucent material of the stamp preview
+struct StampMaterial(Handle<ColorMaterial>);
+
+/// Marks the sprites previewing where the stamp would land
+struct StampPreview;
+
+/// The shape live cells are drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+enum CellShape {
+    #[default]
+    Square,
+    Circle,
+    /// A square with rounded corners, `radius` is a fraction of the cell size from 0.0 to 0.5
+    RoundedSquare {
+        radius: f32,
+    },
+}
+impl CellShape {
+    /// The width and height of the generated mask textures in pixels
+    const TEXTURE_SIZE: u32 = 32;
+
+    /// A white texture that is only opaque inside the shape, `None` for squares which don't need one
+    fn mask_texture(&self) -> Option<Texture> {
+        let radius = match self {
+            CellShape::Square => return None,
+            CellShape::Circle => 0.5,
+            CellShape::RoundedSquare { radius } => radius.clamp(0.0, 0.5),
+        };
+        let size = Self::TEXTURE_SIZE;
+        let mut data = Vec::with_capacity((size * size * 4) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                // The corners are quarter circles centered on the corners of a square inset by `radius`
+                let u = (x as f32 + 0.5) / size as f32 - 0.5;
+                let v = (y as f32 + 0.5) / size as f32 - 0.5;
+                let dx = (u.abs() - (0.5 - radius)).max(0.0);
+                let dy = (v.abs() - (0.5 - radius)).max(0.0);
+                let alpha = if dx * dx + dy * dy <= radius * radius {
+                    255
+                } else {
+                    0
+                };
+                data.extend_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+        Some(Texture::new(
+            Extent3d::new(size, size, 1),
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        ))
+    }
+}
+
+/// Controls optional visual effects that don't affect the simulation, set up with [`GameOfLifePluginBuilder`]
+#[derive(Clone)]
+struct RenderConfig {
+    /// The color of live cells
+    cell_color: Color,
+    /// Shades dead cells up to this many cells away from the nearest live cell, disabled if `None`
+    distance_field: Option<u32>,
+    /// What dragging with shift held draws, cycled with `T`
+    shape_tool: ShapeTool,
+    /// The shape of live cells
+    cell_shape: CellShape,
+    /// Whether cells drawn past the edge of a wrapping topology land on the opposite edge, see [`Topology::wrap_position`](rust_game_of_life::topology::Topology::wrap_position)
+    wrap_drawing: bool,
+    /// Colors every cluster of connected cells differently, disabled if `None`
+    cluster_coloring: Option<ClusterColoring>,
+}
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            cell_color: Color::rgb(0.4, 1.0, 0.6),
+            distance_field: None,
+            shape_tool: ShapeTool::default(),
+            cell_shape: CellShape::default(),
+            wrap_drawing: true,
+            cluster_coloring: None,
+        }
+    }
+}
+
+/// How [`color_clusters`] colors the clusters of [`Universe::clusters`]
+#[derive(Clone, Debug)]
+struct ClusterColoring {
+    /// The colors given to the clusters in order, starting over once every color is used
+    palette: Vec<Color>,
+    /// How many ticks pass between recoloring the clusters, cells born in between keep the live cell color
+    interval: u32,
+}
+impl Default for ClusterColoring {
+    fn default() -> Self {
+        Self {
+            palette: vec![
+                Color::rgb(0.4, 1.0, 0.6),
+                Color::rgb(1.0, 0.6, 0.3),
+                Color::rgb(0.4, 0.6, 1.0),
+                Color::rgb(1.0, 0.9, 0.3),
+                Color::rgb(0.9, 0.4, 0.9),
+                Color::rgb(0.3, 0.9, 0.9),
+            ],
+            interval: 1,
+        }
+    }
+}
+
+/// Materials for [`ClusterColoring::palette`], in the same order
+struct ClusterMaterials(Vec<Handle<ColorMaterial>>);
+
+/// How the camera frames the universe when the app starts, see [`frame_camera`]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+enum CameraFraming {
+    /// Shows the whole universe with the render padding, as fitted by the render systems
+    #[default]
+    FitUniverse,
+    /// Shows the given region of cells
+    Region(Bounds),
+    /// Draws every cell this many pixels wide, centered on the universe
+    Zoom { pixels_per_cell: f32 },
+    /// Shows this many rows of cells, centered on the universe
+    CellsTall(u32),
+}
+impl CameraFraming {
+    /// The camera translation and scale for the framing, `None` if the camera should be left alone.
+    ///
+    /// `render_bounds` are the bounds the render systems fit into the window, which decide where cells are in the world.
+    fn camera_transform(
+        &self,
+        universe_bounds: Bounds,
+        render_bounds: Bounds,
+        window: SizeFloat,
+    ) -> Option<(Vec2, f32)> {
+        let (scale, offset) = render_bounds.fit_into(window);
+        // Matches `position_translation` for points given in cell coordinates
+        let to_world = |x: f32, y: f32| {
+            Vec2::new(
+                offset.x + (x - render_bounds.left as f32) * scale - window.width / 2.0,
+                offset.y + (y - render_bounds.bottom as f32) * scale - window.height / 2.0,
+            )
+        };
+        let center_of = |bounds: Bounds| {
+            to_world(
+                (bounds.left + bounds.right + 1) as f32 / 2.0,
+                (bounds.bottom + bounds.top + 1) as f32 / 2.0,
+            )
+        };
+        match self {
+            CameraFraming::FitUniverse => None,
+            CameraFraming::Region(region) if !region.is_empty() => {
+                let width = (region.right - region.left + 1) as f32 * scale;
+                let height = (region.top - region.bottom + 1) as f32 * scale;
+                let zoom = (width / window.width).max(height / window.height);
+                Some((center_of(*region), zoom))
+            }
+            CameraFraming::Zoom { pixels_per_cell } if !universe_bounds.is_empty() => Some((
+                center_of(universe_bounds),
+                scale / pixels_per_cell.max(f32::EPSILON),
+            )),
+            CameraFraming::CellsTall(rows) if !universe_bounds.is_empty() => Some((
+                center_of(universe_bounds),
+                (*rows).max(1) as f32 * scale / window.height,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Sent whenever the universe ticks, with the births and deaths of the tick
+struct UniverseTicked(TickDelta);
+
+/// Materials for the distance field, the material for distance `n` is at index `n - 1`
+struct FieldMaterials(Vec<Handle<ColorMaterial>>);
+
+/// Marks the background sprites of the distance field
+struct FieldCell;
+
+/// Materials for ghosts of each death cause, the material for `n` remaining generations is at index `n - 1`
+struct GhostMaterials(HashMap<DeathCause, Vec<Handle<ColorMaterial>>>);
+
+/// A fading sprite left behind by a dead cell, which lingers as long as the universe reports decay for its position
+struct Ghost {
+    cause: DeathCause,
+}
+
 fn setup(
     mut commands: Commands,
     sim_config: Res<SimulationConfig>,
+    render_config: Res<RenderConfig>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut textures: ResMut<Assets<Texture>>,
 ) {
     commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    let max_dist = render_config.distance_field.unwrap_or(0);
+    let field_materials = (1..=max_dist)
+        .map(|dist| {
+            // Fade out linearly from a dim version of the live cell color
+            let brightness = 0.3 * (max_dist - dist + 1) as f32 / max_dist as f32;
+            materials.add(Color::rgb(0.4 * brightness, brightness, 0.6 * brightness).into())
+        })
+        .collect();
+    commands.insert_resource(FieldMaterials(field_materials));
+    let generations = sim_config.dead_decay_generations as u32;
+    let ghost_materials = [
+        (DeathCause::Underpopulation, (0.3, 0.5, 1.0)),
+        (DeathCause::Overpopulation, (1.0, 0.4, 0.3)),
+        (DeathCause::OutOfBounds, (0.5, 0.5, 0.5)),
+    ]
+    .iter()
+    .map(|(cause, (r, g, b))| {
+        let fading = (1..=generations)
+            .map(|remaining| {
+                let brightness = 0.5 * remaining as f32 / generations as f32;
+                materials.add(Color::rgb(r * brightness, g * brightness, b * brightness).into())
+            })
+            .collect();
+        (*cause, fading)
+    })
+    .collect();
+    commands.insert_resource(GhostMaterials(ghost_materials));
+    commands.insert_resource(PreviewMaterial(
+        materials.add(Color::rgb(0.8, 0.8, 0.3).into()),
+    ));
+    commands.insert_resource(StampMaterial(
+        materials.add(Color::rgba(0.8, 0.8, 0.3, 0.4).into()),
+    ));
+    // Shapes other than squares are drawn by masking the square sprite with a texture
+    let mask = render_config
+        .cell_shape
+        .mask_texture()
+        .map(|texture| textures.add(texture));
+    let mut cell_material = |color: Color| match &mask {
+        Some(mask) => materials.add(ColorMaterial::modulated_texture(mask.clone(), color)),
+        None => materials.add(color.into()),
+    };
+    let cluster_materials = match &render_config.cluster_coloring {
+        Some(coloring) => coloring
+            .palette
+            .iter()
+            .map(|color| cell_material(*color))
+            .collect(),
+        None => vec![],
+    };
+    commands.insert_resource(ClusterMaterials(cluster_materials));
+    let cell_alive = cell_material(render_config.cell_color);
+    // Dead cells on an alive background are drawn in the clear color
+    let cell_dead = cell_material(Color::rgb(0.0, 0.0, 0.0));
+    commands.insert_resource(BackgroundMaterial(
+        materials.add(render_config.cell_color.into()),
+    ));
     let materials = Materials {
-        cell_alive: materials.add(Color::rgb(0.4, 1.0, 0.6).into()),
+        cell_alive,
+        cell_dead,
     };
     commands.insert_resource(materials.clone());
-    commands.insert_resource(UniverseTimer(Timer::new(sim_config.tick_speed, true)));
+    commands.insert_resource(EditRng(sim_config.generation.rng()));
+    let tick_interval = sim_config.tick_interval().unwrap_or(sim_config.tick_speed);
+    commands.insert_resource(UniverseTimer(Timer::new(tick_interval, true)));
     setup_universe(&mut commands, sim_config, materials)
 }
 
@@ -35,12 +367,15 @@ fn setup_universe(
     sim_config: Res<SimulationConfig>,
     materials: Materials,
 ) {
-    let universe = Universe::generate(
-        commands,
-        materials,
+    let mut universe = Universe::random(
         sim_config.generation.initial_size,
         sim_config.generation.life_chance,
+        &mut sim_config.generation.rng(),
     );
+    universe.materials = materials;
+    // Fast-forward without rendering, then spawn the cell entities only once
+    universe.advance(sim_config.generation.warmup_generations, &sim_config);
+    universe.sync_entities(commands);
     commands.spawn().insert(universe);
 }
 
@@ -49,54 +384,427 @@ fn universe(
     time: Res<Time>,
     mut universe_timer: ResMut<UniverseTimer>,
     mut query: Query<&mut Universe>,
-    sim_config: Res<SimulationConfig>,
+    mut sim_config: ResMut<SimulationConfig>,
+    mut ticked: EventWriter<UniverseTicked>,
+    mut stability: Local<StabilityDetector>,
+) {
+    if let Ok(mut universe) = query.single_mut() {
+        // Without a tick interval the universe ticks every frame
+        let should_tick = match sim_config.tick_interval() {
+            Some(_) => universe_timer.0.tick(time.delta()).just_finished(),
+            None => true,
+        };
+        if should_tick && !sim_config.paused {
+            let delta = universe.tick(&mut commands, &sim_config);
+            ticked.send(UniverseTicked(delta));
+            if let Some(settled) = stability.observe_for_auto_pause(&universe, &sim_config) {
+                info!("Paused, the universe settled: {:?}", settled);
+                sim_config.paused = true;
+            }
+        }
+    }
+}
+
+/// Cycles through the speed presets when `S` is pressed
+fn cycle_speed(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut sim_config: ResMut<SimulationConfig>,
+    mut universe_timer: ResMut<UniverseTimer>,
+) {
+    if keyboard_input.just_pressed(KeyCode::S) {
+        let preset = match sim_config.speed_preset {
+            Some(preset) => preset.next(),
+            None => SpeedPreset::Slow,
+        };
+        sim_config.speed_preset = Some(preset);
+        if let Some(tick_interval) = preset.tick_interval() {
+            universe_timer.0.set_duration(tick_interval);
+            universe_timer.0.reset();
+        }
+        info!("Speed: {:?}", preset);
+    }
+}
+
+/// Moves the live cells back around the origin when `C` is pressed
+fn recenter(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<&mut Universe>,
 ) {
     if let Ok(mut universe) = query.single_mut() {
-        if universe_timer.0.tick(time.delta()).just_finished() && !sim_config.paused {
-            universe.tick(
-                &mut commands,
-                &sim_config.allowed_neighbors,
-                &sim_config.allowed_neighbors_for_birth,
+        let bounds = universe.bounds();
+        if keyboard_input.just_pressed(KeyCode::C) && !bounds.is_empty() {
+            let center = Position::new(
+                (bounds.left + bounds.right).div_euclid(2),
+                (bounds.bottom + bounds.top).div_euclid(2),
             );
+            universe.translate(Position::new(-center.x, -center.y));
+            universe.sync_positions(&mut commands);
+        }
+    }
+}
+
+/// Sprinkles random live cells within the padded bounds when `R` is pressed
+fn sprinkle(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    sim_config: Res<SimulationConfig>,
+    mut rng: ResMut<EditRng>,
+    mut query: Query<&mut Universe>,
+) {
+    if let Ok(mut universe) = query.single_mut() {
+        if keyboard_input.just_pressed(KeyCode::R) {
+            let bounds = sim_config.render_bounds(&universe);
+            universe.sprinkle(&mut commands, bounds, 0.1, &mut rng.0);
+        }
+    }
+}
+
+/// Rewinds one generation per frame while the left arrow key is held
+fn rewind(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut sim_config: ResMut<SimulationConfig>,
+    mut query: Query<&mut Universe>,
+) {
+    if let Ok(mut universe) = query.single_mut() {
+        if keyboard_input.pressed(KeyCode::Left) {
+            sim_config.paused = true;
+            universe.step_back(&mut commands);
+        } else if keyboard_input.just_released(KeyCode::Left) {
+            sim_config.paused = false;
+        }
+    }
+}
+
+/// Leaves fading ghosts behind dead cells, colored by why the cell died
+fn ghost_trails(
+    mut commands: Commands,
+    sim_config: Res<SimulationConfig>,
+    ghost_materials: Res<GhostMaterials>,
+    mut ticks: EventReader<UniverseTicked>,
+    universes: Query<&Universe>,
+    mut ghosts: Query<(Entity, &Position, &Ghost, &mut Handle<ColorMaterial>)>,
+) {
+    if sim_config.dead_decay_generations == 0 {
+        return;
+    }
+    let universe = match universes.iter().next() {
+        Some(universe) => universe,
+        None => return,
+    };
+    // The universe tracks the decay, so a ghost just follows it until it fades out or a cell is born in its place
+    let material_for =
+        |cause: &DeathCause, decay: u8| ghost_materials.0[cause][decay as usize - 1].clone();
+    // Ghosts that faded out or had a cell drawn or born in their place are removed
+    for (entity, pos, _, _) in ghosts.iter() {
+        if universe.dead_decay(*pos).is_none() {
+            commands.entity(entity).despawn();
+        }
+    }
+    for UniverseTicked(delta) in ticks.iter() {
+        for (_, pos, ghost, mut material) in ghosts.iter_mut() {
+            if let Some(decay) = universe.dead_decay(*pos) {
+                *material = material_for(&ghost.cause, decay);
+            }
+        }
+        for (pos, cause) in &delta.deaths {
+            let decay = match universe.dead_decay(*pos) {
+                Some(decay) => decay,
+                None => continue,
+            };
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: material_for(cause, decay),
+                    ..Default::default()
+                })
+                .insert(Ghost { cause: *cause })
+                .insert(*pos)
+                .insert(SizeFloat::new(1.0, 1.0));
+        }
+    }
+}
+
+/// Gives the cells of every cluster the same color from the palette, every [`ClusterColoring::interval`] ticks
+fn color_clusters(
+    render_config: Res<RenderConfig>,
+    cluster_materials: Res<ClusterMaterials>,
+    mut ticks: EventReader<UniverseTicked>,
+    mut ticks_since_coloring: Local<u32>,
+    universes: Query<&Universe>,
+    mut cell_materials: Query<&mut Handle<ColorMaterial>>,
+) {
+    let coloring = match &render_config.cluster_coloring {
+        Some(coloring) if !cluster_materials.0.is_empty() => coloring,
+        _ => return,
+    };
+    *ticks_since_coloring += ticks.iter().count() as u32;
+    if *ticks_since_coloring < coloring.interval.max(1) {
+        return;
+    }
+    *ticks_since_coloring = 0;
+    let universe = match universes.iter().next() {
+        Some(universe) => universe,
+        None => return,
+    };
+    for (index, cluster) in universe.clusters().iter().enumerate() {
+        let material = &cluster_materials.0[index % cluster_materials.0.len()];
+        for pos in cluster {
+            let entity = match universe.cells.get(pos).and_then(|cell| cell.entity) {
+                Some(entity) => entity,
+                None => continue,
+            };
+            if let Ok(mut cell_material) = cell_materials.get_mut(entity) {
+                *cell_material = material.clone();
+            }
+        }
+    }
+}
+
+/// Respawns the distance field sprites whenever the universe changes
+fn distance_field(
+    mut commands: Commands,
+    render_config: Res<RenderConfig>,
+    sim_config: Res<SimulationConfig>,
+    field_materials: Res<FieldMaterials>,
+    universes: Query<&Universe, Changed<Universe>>,
+    field_cells: Query<Entity, With<FieldCell>>,
+) {
+    let max_dist = match render_config.distance_field {
+        Some(max_dist) => max_dist,
+        None => return,
+    };
+    if let Ok(universe) = universes.single() {
+        for entity in field_cells.iter() {
+            commands.entity(entity).despawn();
+        }
+        let bounds = sim_config.render_bounds(&universe);
+        for (pos, dist) in universe.distance_field(bounds, max_dist) {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: field_materials.0[dist as usize - 1].clone(),
+                    ..Default::default()
+                })
+                .insert(FieldCell)
+                .insert(pos)
+                .insert(SizeFloat::new(1.0, 1.0));
         }
     }
 }
 
-// TODO: Fix drawing, the position is a bit wrong
+/// Cycles through the shape tools when `T` is pressed
+fn cycle_shape_tool(keyboard_input: Res<Input<KeyCode>>, mut render_config: ResMut<RenderConfig>) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        render_config.shape_tool = render_config.shape_tool.next();
+        info!("Shape tool: {:?}", render_config.shape_tool);
+    }
+}
+
+/// The cell under the cursor, for universes rendered into `bounds`
+fn cursor_cell(window: &Window, bounds: Bounds, cursor_position: &CursorPosition) -> Position {
+    let (scale, offset) = bounds.fit_into(SizeFloat::new(window.width(), window.height()));
+    // The cursor position is relative to the center of the window
+    Position::new(
+        ((cursor_position.x + window.width() / 2.0 - offset.x) / scale).floor() as i32
+            + bounds.left,
+        ((cursor_position.y + window.height() / 2.0 - offset.y) / scale).floor() as i32
+            + bounds.bottom,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 fn draw_cells(
     mut commands: Commands,
     windows: Res<Windows>,
     mut sim_config: ResMut<SimulationConfig>,
+    render_config: Res<RenderConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
     mouse_button_input: Res<Input<MouseButton>>,
     cursor_position: Res<CursorPosition>,
     mut drawn_positions: ResMut<DrawnPositions>,
+    mut shape_drag: ResMut<ShapeDrag>,
+    preview_material: Res<PreviewMaterial>,
+    previews: Query<Entity, With<ShapePreview>>,
+    stamp: Res<Stamp>,
     mut universes: Query<&mut Universe>,
 ) {
+    // Clicks place the stamp instead, see `stamp_patterns`
+    if stamp.catalog_index.is_some() {
+        return;
+    }
     if let Ok(mut universe) = universes.single_mut() {
+        let window = windows.get_primary().unwrap();
+        let bounds = sim_config.render_bounds(&universe);
+        let cursor_pos = cursor_cell(window, bounds, &cursor_position);
+        let topology = sim_config.topology;
+        let wrap = |pos: Position| {
+            if render_config.wrap_drawing {
+                topology.wrap_position(pos)
+            } else {
+                pos
+            }
+        };
+        let shift =
+            keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
+        if mouse_button_input.just_pressed(MouseButton::Left)
+            && shift
+            && render_config.shape_tool != ShapeTool::Freehand
+        {
+            shape_drag.0 = Some(cursor_pos);
+        }
         if mouse_button_input.pressed(MouseButton::Left) {
             sim_config.paused = true;
-            let window = windows.get_primary().unwrap();
-            let game_size = window.width().min(window.height());
-            let bounds = universe.bounds().with_padding(sim_config.bound_padding);
-            let universe_size = bounds.size();
-            let cursor_pos = Position::new(
-                (cursor_position.x / (game_size / universe_size.width as f32)) as i32,
-                (cursor_position.y / (game_size / universe_size.height as f32)) as i32,
-            );
-            if !drawn_positions.0.contains(&cursor_pos) {
-                universe.toggle_cells_at(
-                    &mut commands,
-                    vec![Position::new(cursor_pos.x, cursor_pos.y)],
-                );
-                drawn_positions.0.push(cursor_pos);
+            match shape_drag.0 {
+                Some(start) => {
+                    for entity in previews.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                    for pos in render_config
+                        .shape_tool
+                        .positions(start, cursor_pos)
+                        .into_iter()
+                        .map(wrap)
+                    {
+                        commands
+                            .spawn_bundle(SpriteBundle {
+                                material: preview_material.0.clone(),
+                                ..Default::default()
+                            })
+                            .insert(ShapePreview)
+                            .insert(pos)
+                            .insert(SizeFloat::new(1.0, 1.0));
+                    }
+                }
+                None => {
+                    let target = wrap(cursor_pos);
+                    if !drawn_positions.0.contains(&target) {
+                        universe.toggle_cells_at(&mut commands, vec![target]);
+                        drawn_positions.0.push(target);
+                    }
+                }
             }
         } else if mouse_button_input.just_released(MouseButton::Left) {
+            if let Some(start) = shape_drag.0.take() {
+                for entity in previews.iter() {
+                    commands.entity(entity).despawn();
+                }
+                // Shapes only add cells, so drawing over existing cells doesn't punch holes in them
+                let mut positions: Vec<Position> = render_config
+                    .shape_tool
+                    .positions(start, cursor_pos)
+                    .into_iter()
+                    .map(wrap)
+                    .collect();
+                // Shapes bigger than a torus wrap onto themselves
+                positions.sort();
+                positions.dedup();
+                positions.retain(|pos| universe.state_at(*pos) == CellState::Dead);
+                universe.toggle_cells_at(&mut commands, positions);
+            }
             sim_config.paused = false;
             drawn_positions.0.clear();
         }
     }
 }
 
+/// Previews the selected [`Stamp`] under the cursor and places it on click
+#[allow(clippy::too_many_arguments)]
+fn stamp_patterns(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    sim_config: Res<SimulationConfig>,
+    render_config: Res<RenderConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor_position: Res<CursorPosition>,
+    mut stamp: ResMut<Stamp>,
+    mut previewed_at: Local<Option<(Position, Bounds)>>,
+    stamp_material: Res<StampMaterial>,
+    previews: Query<Entity, With<StampPreview>>,
+    mut universes: Query<&mut Universe>,
+) {
+    let mut changed = false;
+    if keyboard_input.just_pressed(KeyCode::P) {
+        stamp.catalog_index = match stamp.catalog_index {
+            None => Some(0),
+            Some(index) if index + 1 < catalog::catalog().count() => Some(index + 1),
+            Some(_) => None,
+        };
+        let name = stamp
+            .catalog_index
+            .and_then(|index| catalog::catalog().nth(index))
+            .map(|info| info.name);
+        info!("Stamp: {:?}", name);
+        changed = true;
+    }
+    if keyboard_input.just_pressed(KeyCode::O) {
+        stamp.quarter_turns = (stamp.quarter_turns + 1) % 4;
+        changed = true;
+    }
+    if keyboard_input.just_pressed(KeyCode::M) {
+        stamp.mirrored = !stamp.mirrored;
+        changed = true;
+    }
+    let mut universe = match universes.single_mut() {
+        Ok(universe) => universe,
+        Err(_) => return,
+    };
+    let window = windows.get_primary().unwrap();
+    let render_bounds = sim_config.render_bounds(&universe);
+    let cursor_pos = cursor_cell(window, render_bounds, &cursor_position);
+    let clicked = mouse_button_input.just_pressed(MouseButton::Left);
+    // The preview only has to be respawned when it would look different, the cell under the cursor depends on the render bounds too
+    if !changed && !clicked && *previewed_at == Some((cursor_pos, render_bounds)) {
+        return;
+    }
+    for entity in previews.iter() {
+        commands.entity(entity).despawn();
+    }
+    *previewed_at = None;
+    let pattern = match stamp.pattern() {
+        Some(pattern) => pattern,
+        None => return,
+    };
+    // Wrapped like drawn cells, so the preview shows where the cells actually land
+    let mut cells: Vec<Position> = pattern
+        .translated(cursor_pos)
+        .cells
+        .into_iter()
+        .map(|pos| {
+            if render_config.wrap_drawing {
+                sim_config.topology.wrap_position(pos)
+            } else {
+                pos
+            }
+        })
+        .collect();
+    // Patterns bigger than the space wrap onto themselves
+    cells.sort();
+    cells.dedup();
+    let placed = CellPattern::new(cells);
+    if clicked {
+        // Overlapping placements never fail
+        let _ = universe.insert_pattern(
+            &mut commands,
+            &placed,
+            Position::default(),
+            0,
+            PlacementMode::Overlap,
+        );
+    }
+    for pos in placed.cells {
+        commands
+            .spawn_bundle(SpriteBundle {
+                material: stamp_material.0.clone(),
+                ..Default::default()
+            })
+            .insert(StampPreview)
+            .insert(pos)
+            .insert(SizeFloat::new(1.0, 1.0));
+    }
+    *previewed_at = Some((cursor_pos, render_bounds));
+}
+
 fn position_translation(
     windows: Res<Windows>,
     sim_config: ResMut<SimulationConfig>,
@@ -104,33 +812,77 @@ fn position_translation(
     mut query: Query<(&Position, &mut Transform)>,
 ) {
     if let Ok(universe) = universes.single() {
-        fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
-            let tile_size = bound_window / bound_game;
-            pos * tile_size - bound_window / 2.0
-        }
         let window = windows.get_primary().unwrap();
-        let game_size = window.width().min(window.height());
-        let bounds = universe.bounds().with_padding(sim_config.bound_padding);
-        let universe_size = bounds.size();
+        let bounds = sim_config.render_bounds(&universe);
+        let (scale, offset) = bounds.fit_into(SizeFloat::new(window.width(), window.height()));
+        // Sprites are centered on their translation, which is relative to the center of the window
+        let convert = |pos: i32, edge: i32, offset: f32, window_size: f32| {
+            offset + ((pos - edge) as f32 + 0.5) * scale - window_size / 2.0
+        };
         for (pos, mut transform) in query.iter_mut() {
             transform.translation = Vec3::new(
-                convert(
-                    (pos.x - bounds.left) as f32,
-                    game_size,
-                    universe_size.width as f32,
-                ),
-                convert(
-                    (pos.y - bounds.bottom) as f32,
-                    game_size,
-                    universe_size.height as f32,
-                ),
+                convert(pos.x, bounds.left, offset.x, window.width()),
+                convert(pos.y, bounds.bottom, offset.y, window.height()),
                 0.0,
             );
         }
     }
 }
 
-// TODO: Fix scaling, sprites get stretched in some cases when the bounds don't form a square
+/// Covers an alive background with a single sprite behind the cells, which only store where they differ from it
+fn live_background(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    sim_config: Res<SimulationConfig>,
+    background_material: Res<BackgroundMaterial>,
+    universes: Query<&Universe>,
+    mut backgrounds: Query<(Entity, &mut Transform, &mut Sprite), With<LiveBackground>>,
+) {
+    let universe = match universes.iter().next() {
+        Some(universe) => universe,
+        None => return,
+    };
+    let region = match universe.background() {
+        Some(region) => region,
+        None => {
+            for (entity, _, _) in backgrounds.iter_mut() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+    let window = windows.get_primary().unwrap();
+    let bounds = sim_config.render_bounds(universe);
+    let (scale, offset) = bounds.fit_into(SizeFloat::new(window.width(), window.height()));
+    let size = Vec2::new(
+        (region.right - region.left + 1) as f32 * scale,
+        (region.top - region.bottom + 1) as f32 * scale,
+    );
+    // Centered like the cells in `position_translation`, but behind them
+    let translation = Vec3::new(
+        offset.x + (region.left - bounds.left) as f32 * scale + size.x / 2.0 - window.width() / 2.0,
+        offset.y + (region.bottom - bounds.bottom) as f32 * scale + size.y / 2.0
+            - window.height() / 2.0,
+        -1.0,
+    );
+    match backgrounds.iter_mut().next() {
+        Some((_, mut transform, mut sprite)) => {
+            transform.translation = translation;
+            sprite.size = size;
+        }
+        None => {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    material: background_material.0.clone(),
+                    sprite: Sprite::new(size),
+                    transform: Transform::from_translation(translation),
+                    ..Default::default()
+                })
+                .insert(LiveBackground);
+        }
+    }
+}
+
 fn size_scaling(
     windows: Res<Windows>,
     sim_config: ResMut<SimulationConfig>,
@@ -139,18 +891,49 @@ fn size_scaling(
 ) {
     if let Ok(universe) = universes.single() {
         let window = windows.get_primary().unwrap();
-        let game_size = window.width().min(window.height());
-        let bounds = universe.bounds().with_padding(sim_config.bound_padding);
-        let universe_size = bounds.size();
+        let bounds = sim_config.render_bounds(&universe);
+        let (scale, _) = bounds.fit_into(SizeFloat::new(window.width(), window.height()));
         for (sprite_size, mut sprite) in query.iter_mut() {
-            sprite.size = Vec2::new(
-                sprite_size.width / universe_size.width as f32 * game_size,
-                sprite_size.height / universe_size.height as f32 * game_size,
-            );
+            sprite.size = Vec2::new(sprite_size.width * scale, sprite_size.height * scale);
         }
     }
 }
 
+/// Zooms and moves the camera to the [`CameraFraming`] once the universe and the window exist.
+///
+/// The render systems keep fitting the padded universe into the window every frame, and the framing is a camera zoom on top of that.
+/// It's only applied at startup, so as the universe grows past the framed region its cells shrink just like they do without framing.
+fn frame_camera(
+    mut framed: Local<bool>,
+    framing: Res<CameraFraming>,
+    windows: Res<Windows>,
+    sim_config: Res<SimulationConfig>,
+    universes: Query<&Universe>,
+    mut cameras: Query<&mut Transform, With<Camera>>,
+) {
+    if *framed {
+        return;
+    }
+    let (window, universe) = match (windows.get_primary(), universes.single()) {
+        (Some(window), Ok(universe)) => (window, universe),
+        _ => return,
+    };
+    *framed = true;
+    let window_size = SizeFloat::new(window.width(), window.height());
+    let (translation, zoom) = match framing.camera_transform(
+        universe.bounds(),
+        sim_config.render_bounds(universe),
+        window_size,
+    ) {
+        Some(transform) => transform,
+        None => return,
+    };
+    if let Ok(mut camera) = cameras.single_mut() {
+        camera.translation = translation.extend(camera.translation.z);
+        camera.scale = Vec3::new(zoom, zoom, 1.0);
+    }
+}
+
 /// Gets the cursor position in world coordinates
 fn cursor_position(
     windows: Res<Windows>,
@@ -171,7 +954,304 @@ fn cursor_position(
     }
 }
 
+/// Reads the speed preset from a `--speed <preset>` command line argument
+fn speed_preset_from_args() -> Option<SpeedPreset> {
+    let args: Vec<String> = std::env::args().collect();
+    let name = args
+        .windows(2)
+        .find(|pair| pair[0] == "--speed")
+        .map(|pair| &pair[1])?;
+    match name.parse() {
+        Ok(preset) => Some(preset),
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
+    }
+}
+
+/// Loads the simulation config from the file given with `--config`, read as RON if it ends with `.ron` and as TOML otherwise
+fn sim_config_from_args() -> Option<SimulationConfig> {
+    let args: Vec<String> = std::env::args().collect();
+    let path = args
+        .windows(2)
+        .find(|pair| pair[0] == "--config")
+        .map(|pair| &pair[1])?;
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            eprintln!("Couldn't read config file '{}': {}", path, err);
+            return None;
+        }
+    };
+    let config = if path.ends_with(".ron") {
+        SimulationConfig::from_ron_str(&contents)
+    } else {
+        SimulationConfig::from_toml_str(&contents)
+    };
+    match config {
+        Ok(config) => Some(config),
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
+    }
+}
+
+/// Reads the initial framing from a `--zoom <pixels per cell>`, `--cells-tall <rows>` or `--region <left>,<bottom>,<right>,<top>` command line argument
+fn camera_framing_from_args() -> CameraFraming {
+    let args: Vec<String> = std::env::args().collect();
+    for pair in args.windows(2) {
+        let framing = match pair[0].as_str() {
+            "--zoom" => pair[1]
+                .parse()
+                .map(|pixels_per_cell| CameraFraming::Zoom { pixels_per_cell })
+                .ok(),
+            "--cells-tall" => pair[1].parse().map(CameraFraming::CellsTall).ok(),
+            "--region" => {
+                let edges: Vec<i32> = pair[1]
+                    .split(',')
+                    .filter_map(|edge| edge.trim().parse().ok())
+                    .collect();
+                match edges[..] {
+                    [left, bottom, right, top] => Some(CameraFraming::Region(Bounds {
+                        top,
+                        right,
+                        bottom,
+                        left,
+                    })),
+                    _ => None,
+                }
+            }
+            _ => continue,
+        };
+        match framing {
+            Some(framing) => return framing,
+            None => eprintln!("Invalid value '{}' for {}", pair[1], pair[0]),
+        }
+    }
+    CameraFraming::default()
+}
+
+/// Applies the rendering options given with `--cell-color <r>,<g>,<b>`, `--cell-shape <square|circle|rounded:<radius>>`,
+/// `--distance-field <cells>`, `--ghost-trails <generations>`, `--color-clusters <interval>` and `--no-wrap-drawing` command line arguments
+fn render_options_from_args(mut builder: GameOfLifePluginBuilder) -> GameOfLifePluginBuilder {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--no-wrap-drawing") {
+        builder = builder.wrap_drawing(false);
+    }
+    for pair in args.windows(2) {
+        let value = pair[1].as_str();
+        let applied = match pair[0].as_str() {
+            "--cell-color" => {
+                let channels: Vec<f32> = value
+                    .split(',')
+                    .filter_map(|channel| channel.trim().parse().ok())
+                    .collect();
+                match channels[..] {
+                    [r, g, b] => {
+                        builder = builder.cell_color(Color::rgb(r, g, b));
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            "--cell-shape" => match value {
+                "square" => {
+                    builder = builder.cell_shape(CellShape::Square);
+                    true
+                }
+                "circle" => {
+                    builder = builder.cell_shape(CellShape::Circle);
+                    true
+                }
+                value => match value
+                    .strip_prefix("rounded:")
+                    .and_then(|radius| radius.parse().ok())
+                {
+                    Some(radius) => {
+                        builder = builder.cell_shape(CellShape::RoundedSquare { radius });
+                        true
+                    }
+                    None => false,
+                },
+            },
+            "--distance-field" => match value.parse() {
+                Ok(max_distance) => {
+                    builder = builder.distance_field(Some(max_distance));
+                    true
+                }
+                Err(_) => false,
+            },
+            "--color-clusters" => match value.parse() {
+                Ok(interval) => {
+                    builder = builder.cluster_coloring(Some(ClusterColoring {
+                        interval,
+                        ..Default::default()
+                    }));
+                    true
+                }
+                Err(_) => false,
+            },
+            "--ghost-trails" => match value.parse() {
+                Ok(generations) => {
+                    builder = builder.ghost_trails(generations);
+                    true
+                }
+                Err(_) => false,
+            },
+            _ => continue,
+        };
+        if !applied {
+            eprintln!("Invalid value '{}' for {}", pair[1], pair[0]);
+        }
+    }
+    builder
+}
+
+/// Runs and renders the game of life, see [`GameOfLifePluginBuilder`] for the options
+struct GameOfLifePlugin {
+    sim_config: SimulationConfig,
+    render_config: RenderConfig,
+    framing: CameraFraming,
+}
+impl GameOfLifePlugin {
+    fn builder() -> GameOfLifePluginBuilder {
+        GameOfLifePluginBuilder::default()
+    }
+}
+impl Plugin for GameOfLifePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(self.sim_config.clone())
+            .insert_resource(self.render_config.clone())
+            .insert_resource(self.framing)
+            .add_event::<UniverseTicked>()
+            .insert_resource(CursorPosition { x: 0.0, y: 0.0 })
+            .insert_resource(DrawnPositions(vec![]))
+            .insert_resource(ShapeDrag(None))
+            .insert_resource(Stamp::default())
+            .add_startup_system(setup.system())
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::new()
+                    .with_system(position_translation.system())
+                    .with_system(size_scaling.system())
+                    .with_system(live_background.system()),
+            )
+            .add_system(universe.system().label("universe"))
+            .add_system(distance_field.system().after("universe"))
+            .add_system(ghost_trails.system().after("universe"))
+            .add_system(color_clusters.system().after("universe"))
+            .add_system(cycle_speed.system())
+            .add_system(rewind.system().before("universe"))
+            .add_system(frame_camera.system().before("cursor"))
+            .add_system(cursor_position.system().label("cursor"))
+            .add_system(cycle_shape_tool.system())
+            .add_system(recenter.system())
+            .add_system(sprinkle.system())
+            .add_system(draw_cells.system())
+            .add_system(stamp_patterns.system().after("cursor"));
+    }
+}
+
+/// Collects the simulation and rendering options of a [`GameOfLifePlugin`] in one place.
+///
+/// The plugin lives in the binary, so this example isn't compiled as a doc-test, see the tests at the bottom of this file instead.
+///
+/// ```ignore
+/// App::build()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugin(
+///         GameOfLifePlugin::builder()
+///             .simulation(SimulationConfig::default())
+///             .cell_color(Color::rgb(1.0, 0.8, 0.2))
+///             .cell_shape(CellShape::Circle)
+///             .distance_field(Some(4))
+///             .ghost_trails(8)
+///             .framing(CameraFraming::CellsTall(64))
+///             .cluster_coloring(Some(ClusterColoring::default()))
+///             .wrap_drawing(false)
+///             .build(),
+///     )
+///     .run();
+/// ```
+#[derive(Default)]
+struct GameOfLifePluginBuilder {
+    sim_config: SimulationConfig,
+    render_config: RenderConfig,
+    framing: CameraFraming,
+    /// Overrides [`SimulationConfig::dead_decay_generations`] if set, whichever order the options are given in
+    ghost_generations: Option<u8>,
+}
+impl GameOfLifePluginBuilder {
+    /// The rules and timing of the simulation
+    fn simulation(mut self, sim_config: SimulationConfig) -> Self {
+        self.sim_config = sim_config;
+        self
+    }
+    /// The color of live cells
+    fn cell_color(mut self, color: Color) -> Self {
+        self.render_config.cell_color = color;
+        self
+    }
+    /// The shape live cells are drawn with
+    fn cell_shape(mut self, shape: CellShape) -> Self {
+        self.render_config.cell_shape = shape;
+        self
+    }
+    /// Shades dead cells up to this many cells away from the nearest live cell, disabled if `None`
+    fn distance_field(mut self, max_distance: Option<u32>) -> Self {
+        self.render_config.distance_field = max_distance;
+        self
+    }
+    /// Leaves ghosts behind dead cells that fade out over this many generations, disabled if 0
+    fn ghost_trails(mut self, generations: u8) -> Self {
+        self.ghost_generations = Some(generations);
+        self
+    }
+    /// How the camera frames the universe when the app starts
+    fn framing(mut self, framing: CameraFraming) -> Self {
+        self.framing = framing;
+        self
+    }
+    /// Colors every cluster of connected cells differently, see [`ClusterColoring`]
+    fn cluster_coloring(mut self, coloring: Option<ClusterColoring>) -> Self {
+        self.render_config.cluster_coloring = coloring;
+        self
+    }
+    /// Whether cells drawn past the edge of a wrapping topology land on the opposite edge, on by default
+    fn wrap_drawing(mut self, wrap: bool) -> Self {
+        self.render_config.wrap_drawing = wrap;
+        self
+    }
+    fn build(mut self) -> GameOfLifePlugin {
+        if let Some(generations) = self.ghost_generations {
+            self.sim_config.dead_decay_generations = generations;
+        }
+        GameOfLifePlugin {
+            sim_config: self.sim_config,
+            render_config: self.render_config,
+            framing: self.framing,
+        }
+    }
+}
+
 fn main() {
+    let mut sim_config = sim_config_from_args().unwrap_or_else(|| SimulationConfig {
+        tick_speed: Duration::from_secs_f32(0.1),
+        allowed_neighbors: vec![2, 3],
+        allowed_neighbors_for_birth: vec![3],
+        history_depth: 1000,
+        ..Default::default()
+    });
+    if let Some(preset) = speed_preset_from_args() {
+        sim_config.speed_preset = Some(preset);
+    }
+    if let Err(err) = sim_config.validate() {
+        eprintln!("Invalid simulation config: {}", err);
+        std::process::exit(1);
+    }
+
     App::build()
         .insert_resource(WindowDescriptor {
             title: String::from("Bevy Conway's game of life"),
@@ -180,24 +1260,61 @@ fn main() {
             ..Default::default()
         })
         .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
-        .insert_resource(SimulationConfig {
-            tick_speed: Duration::from_secs_f32(0.1),
-            allowed_neighbors: vec![2, 3],
-            allowed_neighbors_for_birth: vec![3],
-            ..Default::default()
-        })
-        .insert_resource(CursorPosition { x: 0.0, y: 0.0 })
-        .insert_resource(DrawnPositions(vec![]))
         .add_plugins(DefaultPlugins)
-        .add_startup_system(setup.system())
-        .add_system_set_to_stage(
-            CoreStage::PostUpdate,
-            SystemSet::new()
-                .with_system(position_translation.system())
-                .with_system(size_scaling.system()),
+        .add_plugin(
+            render_options_from_args(GameOfLifePlugin::builder())
+                .simulation(sim_config)
+                .framing(camera_framing_from_args())
+                .build(),
         )
-        .add_system(universe.system())
-        .add_system(cursor_position.system())
-        .add_system(draw_cells.system())
         .run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_builder_applies_every_option() {
+        let plugin = GameOfLifePlugin::builder()
+            .ghost_trails(8)
+            .simulation(SimulationConfig {
+                history_depth: 5,
+                ..Default::default()
+            })
+            .cell_color(Color::rgb(1.0, 0.8, 0.2))
+            .cell_shape(CellShape::RoundedSquare { radius: 0.25 })
+            .distance_field(Some(4))
+            .framing(CameraFraming::CellsTall(64))
+            .cluster_coloring(Some(ClusterColoring::default()))
+            .wrap_drawing(false)
+            .build();
+        let mut app = App::build();
+        app.add_plugin(plugin);
+        let world = &app.app.world;
+
+        let sim_config = world.get_resource::<SimulationConfig>().unwrap();
+        assert_eq!(sim_config.history_depth, 5);
+        // The ghost trails apply even though the simulation config was given after them
+        assert_eq!(sim_config.dead_decay_generations, 8);
+        let render_config = world.get_resource::<RenderConfig>().unwrap();
+        assert_eq!(render_config.cell_color, Color::rgb(1.0, 0.8, 0.2));
+        assert_eq!(
+            render_config.cell_shape,
+            CellShape::RoundedSquare { radius: 0.25 }
+        );
+        assert_eq!(render_config.distance_field, Some(4));
+        assert_eq!(
+            render_config
+                .cluster_coloring
+                .as_ref()
+                .map(|coloring| coloring.interval),
+            Some(1)
+        );
+        assert!(!render_config.wrap_drawing);
+        assert_eq!(
+            *world.get_resource::<CameraFraming>().unwrap(),
+            CameraFraming::CellsTall(64)
+        );
+    }
+}